@@ -0,0 +1,27 @@
+//! Helpers shared by the benchmarks comparing event types.
+
+use std::hint::black_box;
+use std::time::Instant;
+
+pub const HANDLERS: usize = 16;
+pub const ITERATIONS: usize = 1_000_000;
+
+/// Creates a handler whose work the optimizer cannot remove.
+pub fn handler(offset: usize) -> impl Fn(usize) + Send + 'static {
+    move |amount| {
+        black_box(amount + offset);
+    }
+}
+
+/// Measures and prints the average time of `invoke` over [`ITERATIONS`] calls.
+pub fn measure<R>(name: &str, invoke: impl Fn(usize) -> R) {
+    let start = Instant::now();
+    for i in 0..ITERATIONS {
+        black_box(invoke(black_box(i)));
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{name:>12}: {:>8.2} ns/invoke ({HANDLERS} handlers)",
+        elapsed.as_nanos() as f64 / ITERATIONS as f64
+    );
+}
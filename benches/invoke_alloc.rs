@@ -0,0 +1,54 @@
+//! Counts the heap allocations performed per [`Event::invoke`] call.
+//!
+//! Run with `cargo bench --bench invoke_alloc`.
+
+use event_handler::Event;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const HANDLERS: usize = 16;
+const ITERATIONS: usize = 100_000;
+
+/// A global allocator counting the number of allocations.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn handler(offset: usize) -> impl Fn(usize) + Send + 'static {
+    move |amount| {
+        black_box(amount + offset);
+    }
+}
+
+fn main() {
+    let event = Event::new();
+    let _handles: Vec<_> = (0..HANDLERS)
+        .map(|offset| event.add_fn(handler(offset)).unwrap())
+        .collect();
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for i in 0..ITERATIONS {
+        event.invoke(black_box(i));
+    }
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+
+    println!(
+        "{:.3} allocations/invoke ({HANDLERS} Fn handlers)",
+        allocations as f64 / ITERATIONS as f64
+    );
+}
@@ -0,0 +1,23 @@
+//! Compares single-threaded invocation latency of [`LocalEvent`] against the locked [`Event`].
+//!
+//! Run with `cargo bench --bench local_event`.
+
+mod common;
+
+use common::{handler, measure, HANDLERS};
+use event_handler::{Event, LocalEvent};
+
+fn main() {
+    let local_event = LocalEvent::new();
+    for offset in 0..HANDLERS {
+        local_event.add_fn(handler(offset));
+    }
+
+    let event = Event::new();
+    let _handles: Vec<_> = (0..HANDLERS)
+        .map(|offset| event.add_fn(handler(offset)).unwrap())
+        .collect();
+
+    measure("LocalEvent", |args| local_event.invoke(args));
+    measure("Event", |args| event.invoke(args));
+}
@@ -0,0 +1,23 @@
+//! Compares invocation throughput of [`StaticEvent`] against the boxed [`Event`].
+//!
+//! Run with `cargo bench --bench static_event`.
+
+mod common;
+
+use common::{handler, measure, HANDLERS};
+use event_handler::{Event, StaticEvent};
+
+fn main() {
+    let mut static_event = StaticEvent::new();
+    for offset in 0..HANDLERS {
+        static_event.add(handler(offset));
+    }
+
+    let event = Event::new();
+    let _handles: Vec<_> = (0..HANDLERS)
+        .map(|offset| event.add_fn(handler(offset)).unwrap())
+        .collect();
+
+    measure("StaticEvent", |args| static_event.invoke(args));
+    measure("Event", |args| event.invoke(args));
+}
@@ -0,0 +1,80 @@
+use crate::depth;
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+thread_local! {
+    /// The accumulators of the currently running invocations on this thread,
+    /// innermost invocation last.
+    static ACCUMULATORS: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An accumulator provided to the handlers of an invocation.
+#[derive(Clone, Copy)]
+struct Entry {
+    /// The address of the invoked event.
+    event: usize,
+    /// The number of invocations of the event running when the invocation started.
+    depth: usize,
+    /// The type of the accumulator.
+    type_id: TypeId,
+    ptr: *const (),
+}
+
+/// Calls `f` with the accumulator of the running invocation of the specified event,
+/// if it is of type `Acc`.
+///
+/// Handlers of other events, and of invocations of the same event raised from within
+/// a handler, do not see the accumulator.
+pub(crate) fn with_current<Acc, R>(event: usize, f: impl FnOnce(&Mutex<Acc>) -> R) -> Option<R>
+where
+    Acc: 'static,
+{
+    // The handlers run one level deeper than the invocation that provided the accumulator.
+    let depth = depth::current_depth(event).checked_sub(1)?;
+    let current = ACCUMULATORS.with(|accumulators| {
+        accumulators
+            .borrow()
+            .iter()
+            .rev()
+            .find(|entry| entry.event == event && entry.depth == depth)
+            .copied()
+    });
+    match current {
+        Some(entry) if entry.type_id == TypeId::of::<Acc>() => {
+            // The pointer was registered by an `AccumulatorScope` that outlives this call.
+            let accumulator = unsafe { &*(entry.ptr as *const Mutex<Acc>) };
+            Some(f(accumulator))
+        }
+        _ => None,
+    }
+}
+
+/// Provides an accumulator to the handlers of the next invocation of an event until dropped.
+pub(crate) struct AccumulatorScope<'a, Acc> {
+    _accumulator: &'a Mutex<Acc>,
+}
+
+impl<'a, Acc> AccumulatorScope<'a, Acc>
+where
+    Acc: 'static,
+{
+    pub(crate) fn enter(event: usize, accumulator: &'a Mutex<Acc>) -> Self {
+        let entry = Entry {
+            event,
+            depth: depth::current_depth(event),
+            type_id: TypeId::of::<Acc>(),
+            ptr: accumulator as *const Mutex<Acc> as *const (),
+        };
+        ACCUMULATORS.with(|accumulators| accumulators.borrow_mut().push(entry));
+        Self {
+            _accumulator: accumulator,
+        }
+    }
+}
+
+impl<Acc> Drop for AccumulatorScope<'_, Acc> {
+    fn drop(&mut self) {
+        ACCUMULATORS.with(|accumulators| accumulators.borrow_mut().pop());
+    }
+}
@@ -0,0 +1,70 @@
+use std::alloc::{handle_alloc_error, Layout};
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+/// An allocator for boxed handlers, see [`Event::with_allocator`](crate::Event::with_allocator).
+///
+/// ## Safety
+/// Implementations must uphold the same contract as [`GlobalAlloc`](std::alloc::GlobalAlloc):
+/// returned memory must be valid for the requested layout until it is deallocated.
+pub unsafe trait HandlerAllocator: Send + Sync {
+    /// Allocates memory for the specified non-zero-sized layout.
+    /// Returns a null pointer if the allocation failed.
+    ///
+    /// ## Safety
+    /// `layout` must have a non-zero size.
+    unsafe fn allocate(&self, layout: Layout) -> *mut u8;
+
+    /// Deallocates memory previously returned by [`HandlerAllocator::allocate`].
+    ///
+    /// ## Safety
+    /// `ptr` must have been allocated by this allocator with the same `layout`.
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout);
+}
+
+/// A closure boxed through a [`HandlerAllocator`].
+pub(crate) struct AllocatedFn<TEventArgs> {
+    /// The closure.
+    ptr: NonNull<dyn Fn(TEventArgs) + Send>,
+    /// The allocator owning the memory of the closure.
+    allocator: Arc<dyn HandlerAllocator>,
+}
+
+unsafe impl<TEventArgs> Send for AllocatedFn<TEventArgs> {}
+
+impl<TEventArgs> AllocatedFn<TEventArgs> {
+    pub(crate) fn new<T>(handler: T, allocator: Arc<dyn HandlerAllocator>) -> Self
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let layout = Layout::new::<T>();
+        let ptr = if layout.size() == 0 {
+            NonNull::<T>::dangling()
+        } else {
+            let ptr = unsafe { allocator.allocate(layout) } as *mut T;
+            NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout))
+        };
+
+        unsafe { ptr.as_ptr().write(handler) };
+        Self { ptr, allocator }
+    }
+
+    /// Calls the closure.
+    pub(crate) fn call(&self, args: TEventArgs) {
+        let handler = unsafe { self.ptr.as_ref() };
+        handler(args)
+    }
+}
+
+impl<TEventArgs> Drop for AllocatedFn<TEventArgs> {
+    fn drop(&mut self) {
+        unsafe {
+            let layout = Layout::for_value(self.ptr.as_ref());
+            std::ptr::drop_in_place(self.ptr.as_ptr());
+            if layout.size() != 0 {
+                self.allocator
+                    .deallocate(self.ptr.as_ptr() as *mut u8, layout);
+            }
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{HandleKey, RegistrationId};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// An asynchronous handler stored in an [`AsyncEvent`].
+type AsyncHandler<TEventArgs> =
+    Arc<dyn Fn(TEventArgs) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// An event whose handler map is guarded by an asynchronous lock.
+///
+/// Registrations and invocations await the lock instead of blocking the thread,
+/// and handlers are awaited while the lock is held. Registering a handler during
+/// a running invocation therefore waits for the invocation to complete without
+/// blocking the runtime.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::AsyncEvent;
+///
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// let event = AsyncEvent::new();
+/// let id = event.add_fn(|value: i32| async move { assert_eq!(value, 7) }).await;
+///
+/// assert_eq!(event.invoke(7).await, 1);
+/// assert!(event.remove(id).await);
+/// # });
+/// ```
+pub struct AsyncEvent<TEventArgs> {
+    handlers: RwLock<BTreeMap<RegistrationId, AsyncHandler<TEventArgs>>>,
+}
+
+impl<TEventArgs> AsyncEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            handlers: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Registers an asynchronous closure.
+    ///
+    /// ## Returns
+    /// The identifier to pass to [`AsyncEvent::remove`].
+    pub async fn add_fn<F, Fut>(&self, handler: F) -> RegistrationId
+    where
+        F: Fn(TEventArgs) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler: AsyncHandler<TEventArgs> = Arc::new(move |args| Box::pin(handler(args)));
+        let id = RegistrationId(HandleKey::next_id());
+        self.handlers.write().await.insert(id, handler);
+        id
+    }
+
+    /// Unregisters the specified handler.
+    ///
+    /// Returns whether the handler was registered.
+    pub async fn remove(&self, id: RegistrationId) -> bool {
+        self.handlers.write().await.remove(&id).is_some()
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub async fn len(&self) -> usize {
+        self.handlers.read().await.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Invokes the event, awaiting each handler in turn.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The number of handlers called.
+    pub async fn invoke(&self, args: TEventArgs) -> usize {
+        let handlers = self.handlers.read().await;
+        for handler in handlers.values() {
+            handler(args.clone()).await;
+        }
+        handlers.len()
+    }
+}
+
+impl<TEventArgs> Default for AsyncEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn registration_waits_for_running_invocation() {
+        let event = AsyncEvent::new();
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let counter = count.clone();
+        event
+            .add_fn(move |amount: usize| {
+                let counter = counter.clone();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    counter.fetch_add(amount, Ordering::SeqCst);
+                }
+            })
+            .await;
+
+        let (fired, _) = tokio::join!(event.invoke(2), event.add_fn(|_| async {}));
+        assert_eq!(fired, 1);
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+        assert_eq!(event.len().await, 2);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::RegistrationId;
+use std::collections::VecDeque;
+use std::time::SystemTime;
+
+/// A record of a single invocation, see [`Event::audit_log`](crate::Event::audit_log).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// The time the invocation completed.
+    pub timestamp: SystemTime,
+    /// The number of invocations recorded before this one.
+    pub sequence: u64,
+    /// The handlers that were called, in order.
+    pub fired: Vec<RegistrationId>,
+}
+
+/// A ring buffer holding the most recent invocations.
+pub(crate) struct AuditLog {
+    /// The maximum number of entries to keep.
+    capacity: usize,
+    /// The sequence number of the next entry.
+    sequence: u64,
+    entries: VecDeque<AuditEntry>,
+}
+
+impl AuditLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            sequence: 0,
+            entries: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records an invocation, evicting the oldest entry if the log is full.
+    pub(crate) fn record(&mut self, fired: Vec<RegistrationId>) {
+        let entry = AuditEntry {
+            timestamp: SystemTime::now(),
+            sequence: self.sequence,
+            fired,
+        };
+        self.sequence += 1;
+
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Returns the recorded entries, oldest first.
+    pub(crate) fn entries(&self) -> Vec<AuditEntry> {
+        self.entries.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oldest_entries_are_evicted() {
+        let mut log = AuditLog::new(0);
+        log.record(Vec::new());
+        assert!(log.entries().is_empty());
+
+        let mut log = AuditLog::new(1);
+        log.record(Vec::new());
+        log.record(Vec::new());
+        assert_eq!(log.entries().len(), 1);
+        assert_eq!(log.entries()[0].sequence, 1);
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{AddHandlerError, Event, EventHandle, RegistrationId};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// An event delivering each invocation to exactly one handler, chosen by
+/// smooth weighted round-robin.
+///
+/// This is useful when handlers represent workers sharing a load (unicast)
+/// rather than listeners of a broadcast.
+pub struct BalancedEvent<TEventArgs> {
+    event: Event<TEventArgs>,
+    slots: Mutex<BTreeMap<RegistrationId, Slot>>,
+}
+
+/// The scheduling state of a handler.
+struct Slot {
+    /// The configured weight.
+    weight: u32,
+    /// The current weight, accumulating while the handler is not chosen.
+    current: i64,
+}
+
+impl<TEventArgs> BalancedEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            event: Event::new(),
+            slots: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Registers a closure with the specified weight.
+    ///
+    /// Handlers are chosen proportionally to their weight; handlers with
+    /// a weight of zero are never chosen.
+    pub fn add_fn<T>(
+        &self,
+        weight: u32,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let handle = self.event.add_fn(handler)?;
+        let slot = Slot { weight, current: 0 };
+        self.slots.lock().unwrap().insert(handle.id(), slot);
+        Ok(handle)
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Invokes a single handler, chosen by weighted round-robin.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The registration of the chosen handler, or `None` if no handler could be chosen.
+    pub fn invoke(&self, args: TEventArgs) -> Option<RegistrationId> {
+        let chosen = self.choose()?;
+        self.event.invoke_with_order(args, &[chosen]);
+        Some(chosen)
+    }
+
+    /// Chooses the next handler.
+    fn choose(&self) -> Option<RegistrationId> {
+        let mut slots = self.slots.lock().unwrap();
+        slots.retain(|id, _| self.event.is_registered(*id));
+
+        let total: i64 = slots.values().map(|slot| i64::from(slot.weight)).sum();
+        if total == 0 {
+            return None;
+        }
+
+        for slot in slots.values_mut() {
+            slot.current += i64::from(slot.weight);
+        }
+
+        let (id, slot) = slots
+            .iter_mut()
+            .filter(|(_, slot)| slot.weight > 0)
+            .reduce(|best, candidate| {
+                if candidate.1.current > best.1.current {
+                    candidate
+                } else {
+                    best
+                }
+            })?;
+        slot.current -= total;
+        Some(*id)
+    }
+}
+
+impl<TEventArgs> Default for BalancedEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn equal_weights_alternate() {
+        let event = BalancedEvent::new();
+        let counts = Arc::new(Mutex::new([0; 2]));
+        let handles: Vec<_> = (0..2)
+            .map(|index| {
+                let counts = counts.clone();
+                event
+                    .add_fn(1, move |_: ()| counts.lock().unwrap()[index] += 1)
+                    .unwrap()
+            })
+            .collect();
+
+        let chosen: Vec<_> = (0..4).map(|_| event.invoke(()).unwrap()).collect();
+        assert_eq!(*counts.lock().unwrap(), [2, 2]);
+        assert_ne!(chosen[0], chosen[1]);
+        assert!(chosen
+            .iter()
+            .all(|id| handles.iter().any(|h| h.id() == *id)));
+    }
+
+    #[test]
+    fn weights_are_proportional() {
+        let event = BalancedEvent::new();
+        let counts = Arc::new(Mutex::new([0; 2]));
+        let _handles: Vec<_> = [3, 1]
+            .into_iter()
+            .enumerate()
+            .map(|(index, weight)| {
+                let counts = counts.clone();
+                event
+                    .add_fn(weight, move |_: ()| counts.lock().unwrap()[index] += 1)
+                    .unwrap()
+            })
+            .collect();
+
+        for _ in 0..8 {
+            event.invoke(());
+        }
+        assert_eq!(*counts.lock().unwrap(), [6, 2]);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{AddHandlerError, Event, EventHandle};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// An event that buffers its arguments and delivers them to the handlers in batches.
+///
+/// A batch is delivered once it holds `batch_size` arguments or, if a window is
+/// configured, once its oldest arguments were buffered for longer than the window.
+/// Both conditions are checked by [`BatchEvent::invoke`]; [`BatchEvent::flush`]
+/// delivers the buffered arguments regardless.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::BatchEvent;
+/// use std::sync::{Arc, Mutex};
+///
+/// let event = BatchEvent::new(2);
+/// let sum = Arc::new(Mutex::new(0));
+/// let _handle = event.add_fn_batch({
+///     let sum = sum.clone();
+///     move |batch: &[i32]| *sum.lock().unwrap() += batch.iter().sum::<i32>()
+/// }).unwrap();
+///
+/// event.invoke(1);
+/// assert_eq!(*sum.lock().unwrap(), 0);
+/// event.invoke(2);
+/// assert_eq!(*sum.lock().unwrap(), 3);
+/// ```
+pub struct BatchEvent<TEventArgs> {
+    event: Event<Arc<[TEventArgs]>>,
+    batch_size: usize,
+    window: Option<Duration>,
+    buffer: Mutex<Buffer<TEventArgs>>,
+}
+
+/// The arguments of the pending batch.
+struct Buffer<TEventArgs> {
+    args: Vec<TEventArgs>,
+    /// The time the oldest arguments were buffered.
+    since: Option<Instant>,
+}
+
+impl<TEventArgs> BatchEvent<TEventArgs> {
+    /// Creates an event delivering batches of `batch_size` arguments.
+    pub fn new(batch_size: usize) -> Self {
+        Self {
+            event: Event::new(),
+            batch_size: batch_size.max(1),
+            window: None,
+            buffer: Mutex::new(Buffer {
+                args: Vec::new(),
+                since: None,
+            }),
+        }
+    }
+
+    /// Creates an event delivering batches of `batch_size` arguments, or smaller
+    /// batches once their oldest arguments were buffered for longer than `window`.
+    pub fn with_window(batch_size: usize, window: Duration) -> Self {
+        Self {
+            window: Some(window),
+            ..Self::new(batch_size)
+        }
+    }
+
+    /// Registers a closure that receives batches of arguments.
+    pub fn add_fn_batch<H>(
+        &self,
+        handler: H,
+    ) -> Result<EventHandle<Arc<[TEventArgs]>>, AddHandlerError>
+    where
+        H: Fn(&[TEventArgs]) + Send + 'static,
+    {
+        self.event
+            .add_fn(move |batch: Arc<[TEventArgs]>| handler(&batch))
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of arguments waiting for delivery.
+    pub fn buffered(&self) -> usize {
+        self.buffer.lock().unwrap().args.len()
+    }
+
+    /// Buffers the arguments, delivering the batch if it is complete.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// Whether a batch was delivered.
+    pub fn invoke(&self, args: TEventArgs) -> bool {
+        let batch = {
+            let mut buffer = self.buffer.lock().unwrap();
+            let since = *buffer.since.get_or_insert_with(Instant::now);
+            buffer.args.push(args);
+
+            let expired = matches!(self.window, Some(window) if since.elapsed() >= window);
+            if buffer.args.len() < self.batch_size && !expired {
+                return false;
+            }
+            Self::take(&mut buffer)
+        };
+
+        self.event.invoke(batch);
+        true
+    }
+
+    /// Delivers the buffered arguments, if any, as an incomplete batch.
+    ///
+    /// ## Returns
+    /// Whether a batch was delivered.
+    pub fn flush(&self) -> bool {
+        let batch = {
+            let mut buffer = self.buffer.lock().unwrap();
+            if buffer.args.is_empty() {
+                return false;
+            }
+            Self::take(&mut buffer)
+        };
+
+        self.event.invoke(batch);
+        true
+    }
+
+    /// Empties the buffer, returning its arguments as a batch.
+    fn take(buffer: &mut Buffer<TEventArgs>) -> Arc<[TEventArgs]> {
+        buffer.since = None;
+        std::mem::take(&mut buffer.args).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn complete_batches_are_delivered() {
+        let event = BatchEvent::new(3);
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let batches = batches.clone();
+            event.add_fn_batch(move |batch: &[i32]| batches.lock().unwrap().push(batch.to_vec()))
+        }
+        .unwrap();
+
+        let delivered: Vec<_> = (1..=5).map(|value| event.invoke(value)).collect();
+        assert_eq!(delivered, vec![false, false, true, false, false]);
+        assert_eq!(*batches.lock().unwrap(), vec![vec![1, 2, 3]]);
+        assert_eq!(event.buffered(), 2);
+
+        assert!(event.flush());
+        assert_eq!(batches.lock().unwrap()[1], vec![4, 5]);
+    }
+
+    #[test]
+    fn expired_window_delivers_incomplete_batch() {
+        let event = BatchEvent::with_window(10, Duration::ZERO);
+        let _handle = event
+            .add_fn_batch(|batch: &[i32]| assert_eq!(batch, [1]))
+            .unwrap();
+        assert!(event.invoke(1));
+    }
+}
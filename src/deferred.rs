@@ -0,0 +1,72 @@
+use crate::routed;
+use std::cell::RefCell;
+
+/// A closure deferred until the end of the current invocation.
+type Deferred = Box<dyn FnOnce()>;
+
+thread_local! {
+    /// The closures deferred by the currently running deferred invocations on this thread,
+    /// innermost invocation last.
+    static DEFERRED: RefCell<Vec<Vec<Deferred>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The context of an invocation, passed to handlers registered via
+/// [`Event::add_fn_with_context`](crate::Event::add_fn_with_context).
+pub struct InvokeContext {
+    _private: (),
+}
+
+impl InvokeContext {
+    pub(crate) const fn new() -> Self {
+        Self { _private: () }
+    }
+
+    /// Defers a closure until all handlers of the current
+    /// [`Event::invoke_deferred`](crate::Event::invoke_deferred) call have finished.
+    ///
+    /// Deferred closures run in the order they were deferred. Outside of a deferred
+    /// invocation, the closure runs immediately.
+    pub fn defer<F>(&self, f: F)
+    where
+        F: FnOnce() + 'static,
+    {
+        let mut f = Some(Box::new(f) as Deferred);
+        DEFERRED.with(|deferred| {
+            if let Some(queue) = deferred.borrow_mut().last_mut() {
+                queue.extend(f.take());
+            }
+        });
+
+        if let Some(f) = f {
+            f();
+        }
+    }
+
+    /// Marks the current [`Event::invoke_routed`](crate::Event::invoke_routed) call as handled.
+    ///
+    /// Returns `false` if the handler is not called by a routed invocation.
+    pub fn set_handled(&self) -> bool {
+        routed::mark_handled()
+    }
+}
+
+/// Collects deferred closures until dropped, then runs them.
+pub(crate) struct DeferScope {
+    _private: (),
+}
+
+impl DeferScope {
+    pub(crate) fn enter() -> Self {
+        DEFERRED.with(|deferred| deferred.borrow_mut().push(Vec::new()));
+        Self { _private: () }
+    }
+}
+
+impl Drop for DeferScope {
+    fn drop(&mut self) {
+        let queue = DEFERRED.with(|deferred| deferred.borrow_mut().pop());
+        for f in queue.into_iter().flatten() {
+            f();
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{AddHandlerError, Event, EventHandle};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A strategy for delivering the arguments of a [`DeliveryEvent`] to its handlers.
+pub trait DeliveryMode<T> {
+    /// The arguments the handlers receive.
+    type Args: Clone;
+
+    /// Prepares a freshly created event for this mode.
+    fn configure(_event: &Event<Self::Args>) {}
+
+    /// Delivers the value to the handlers of the event.
+    fn deliver(event: &Event<Self::Args>, value: T);
+}
+
+/// Every handler receives its own clone of the value.
+pub struct CloneEach;
+
+/// The value is wrapped in an [`Arc`] once; every handler receives a clone of the `Arc`.
+pub struct SharedArc;
+
+/// The value is moved into the sole handler without being cloned.
+///
+/// The event accepts a single handler; further registrations fail with
+/// [`AddHandlerError::CapacityExceeded`].
+pub struct MoveSingle;
+
+impl<T> DeliveryMode<T> for CloneEach
+where
+    T: Clone,
+{
+    type Args = T;
+
+    fn deliver(event: &Event<T>, value: T) {
+        event.invoke(value);
+    }
+}
+
+impl<T> DeliveryMode<T> for SharedArc {
+    type Args = Arc<T>;
+
+    fn deliver(event: &Event<Arc<T>>, value: T) {
+        event.invoke(Arc::new(value));
+    }
+}
+
+impl<T> DeliveryMode<T> for MoveSingle
+where
+    T: Clone,
+{
+    type Args = T;
+
+    fn configure(event: &Event<T>) {
+        event.set_max_handlers(1);
+    }
+
+    fn deliver(event: &Event<T>, value: T) {
+        event.invoke_moved(value);
+    }
+}
+
+/// An event whose argument delivery strategy is selected at compile time.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::{DeliveryEvent, SharedArc};
+/// use std::sync::Arc;
+///
+/// let event = DeliveryEvent::<Vec<u8>, SharedArc>::new();
+/// let _handle = event.add_fn(|bytes: Arc<Vec<u8>>| assert_eq!(bytes.len(), 3)).unwrap();
+/// event.invoke(vec![1, 2, 3]);
+/// ```
+pub struct DeliveryEvent<T, M>
+where
+    M: DeliveryMode<T>,
+{
+    event: Event<M::Args>,
+    mode: PhantomData<fn(T) -> M>,
+}
+
+impl<T, M> DeliveryEvent<T, M>
+where
+    M: DeliveryMode<T>,
+{
+    pub fn new() -> Self {
+        let event = Event::new();
+        M::configure(&event);
+        Self {
+            event,
+            mode: PhantomData,
+        }
+    }
+
+    pub fn add_fn<H>(&self, handler: H) -> Result<EventHandle<M::Args>, AddHandlerError>
+    where
+        H: Fn(M::Args) + Send + 'static,
+    {
+        self.event.add_fn(handler)
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Invokes the event, delivering the value according to the mode.
+    ///
+    /// ## Arguments
+    /// * `value` - The event arguments.
+    pub fn invoke(&self, value: T) {
+        M::deliver(&self.event, value)
+    }
+}
+
+impl<T, M> Default for DeliveryEvent<T, M>
+where
+    M: DeliveryMode<T>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Counts how often it was cloned.
+    struct Counted(Arc<AtomicUsize>);
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Self(self.0.clone())
+        }
+    }
+
+    fn register<M>(
+        event: &DeliveryEvent<Counted, M>,
+        calls: &Arc<AtomicUsize>,
+    ) -> EventHandle<M::Args>
+    where
+        M: DeliveryMode<Counted>,
+    {
+        let calls = calls.clone();
+        event
+            .add_fn(move |_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn clone_each_clones_per_handler() {
+        let (clones, calls) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let event = DeliveryEvent::<_, CloneEach>::new();
+        let _handles = [register(&event, &calls), register(&event, &calls)];
+
+        event.invoke(Counted(clones.clone()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(clones.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn shared_arc_never_clones_the_value() {
+        let (clones, calls) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let event = DeliveryEvent::<_, SharedArc>::new();
+        let _handles = [register(&event, &calls), register(&event, &calls)];
+
+        let shared = Arc::new(Mutex::new(None));
+        let _capture = {
+            let shared = shared.clone();
+            event.add_fn(move |args| *shared.lock().unwrap() = Some(args))
+        }
+        .unwrap();
+
+        event.invoke(Counted(clones.clone()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(clones.load(Ordering::SeqCst), 0);
+        assert!(shared.lock().unwrap().is_some());
+    }
+
+    #[test]
+    fn move_single_moves_into_the_sole_handler() {
+        let (clones, calls) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+        let event = DeliveryEvent::<_, MoveSingle>::new();
+        let _handle = register(&event, &calls);
+        assert_eq!(
+            event.add_fn(|_| {}).err(),
+            Some(AddHandlerError::CapacityExceeded)
+        );
+
+        event.invoke(Counted(clones.clone()));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(clones.load(Ordering::SeqCst), 0);
+    }
+}
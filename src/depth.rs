@@ -0,0 +1,40 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    /// The number of nested invocations running on this thread, keyed by the address of
+    /// the event, or of the handler for handlers that must not be reentered.
+    static DEPTHS: RefCell<HashMap<usize, usize>> = RefCell::new(HashMap::new());
+}
+
+/// Returns the number of nested invocations of the specified event running on this thread.
+pub(crate) fn current_depth(event: usize) -> usize {
+    DEPTHS.with(|depths| depths.borrow().get(&event).copied().unwrap_or(0))
+}
+
+/// Tracks an invocation of an event or handler on this thread until dropped.
+pub(crate) struct DepthScope {
+    /// The address of the event or handler.
+    event: usize,
+}
+
+impl DepthScope {
+    pub(crate) fn enter(event: usize) -> Self {
+        DEPTHS.with(|depths| *depths.borrow_mut().entry(event).or_insert(0) += 1);
+        Self { event }
+    }
+}
+
+impl Drop for DepthScope {
+    fn drop(&mut self) {
+        DEPTHS.with(|depths| {
+            let mut depths = depths.borrow_mut();
+            if let Some(depth) = depths.get_mut(&self.event) {
+                *depth -= 1;
+                if *depth == 0 {
+                    depths.remove(&self.event);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{AddHandlerError, Event, EventHandle};
+use std::sync::Mutex;
+
+/// Values that can describe their difference to a previous value.
+pub trait Diff {
+    /// The description of a difference.
+    type Delta;
+
+    /// Computes the difference from `previous` to `self`.
+    fn diff(&self, previous: &Self) -> Self::Delta;
+}
+
+/// The value delivered to the handlers of a [`DiffEvent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change<T, D> {
+    /// The full value, delivered on the first invocation and to handlers
+    /// registered after it.
+    Snapshot(T),
+    /// The difference to the previously invoked value.
+    Delta(D),
+}
+
+/// An event that delivers the difference to the previously invoked value
+/// instead of the full value.
+///
+/// Invocations are serialized, so handlers receive the deltas in the order
+/// the values were invoked. Handlers must therefore not invoke the event.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::{Change, Diff, DiffEvent};
+/// use std::sync::{Arc, Mutex};
+///
+/// #[derive(Clone)]
+/// struct Counter(i32);
+///
+/// impl Diff for Counter {
+///     type Delta = i32;
+///
+///     fn diff(&self, previous: &Self) -> i32 {
+///         self.0 - previous.0
+///     }
+/// }
+///
+/// let event = DiffEvent::new();
+/// let last = Arc::new(Mutex::new(None));
+/// let _handle = event.add_fn_delta({
+///     let last = last.clone();
+///     move |change: Change<_, _>| {
+///         if let Change::Delta(delta) = change {
+///             *last.lock().unwrap() = Some(delta);
+///         }
+///     }
+/// }).unwrap();
+///
+/// event.invoke(Counter(3));
+/// event.invoke(Counter(5));
+/// assert_eq!(*last.lock().unwrap(), Some(2));
+/// ```
+pub struct DiffEvent<T>
+where
+    T: Diff + Clone,
+    T::Delta: Clone,
+{
+    event: Event<Change<T, T::Delta>>,
+    /// The previously invoked value; locked while handlers are called.
+    previous: Mutex<Option<T>>,
+}
+
+impl<T> DiffEvent<T>
+where
+    T: Diff + Clone,
+    T::Delta: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            event: Event::new(),
+            previous: Mutex::new(None),
+        }
+    }
+
+    /// Registers a closure that receives a snapshot on the first invocation
+    /// and deltas afterwards.
+    ///
+    /// If the event was invoked before, the closure immediately receives a snapshot
+    /// of the previously invoked value, which the following deltas refer to.
+    pub fn add_fn_delta<H>(
+        &self,
+        handler: H,
+    ) -> Result<EventHandle<Change<T, T::Delta>>, AddHandlerError>
+    where
+        H: Fn(Change<T, T::Delta>) + Send + 'static,
+    {
+        // Holding the lock keeps invocations from delivering deltas before the snapshot.
+        let previous = self.previous.lock().unwrap();
+        let handle = self.event.add_fn(handler)?;
+
+        if let Some(previous) = &*previous {
+            let handlers = &self.event.handlers;
+            if let Some((_, entry)) = handlers.current().get_key_value(&handle.key) {
+                handlers.call(entry, Change::Snapshot(previous.clone()));
+            }
+        }
+        Ok(handle)
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Invokes the event with the difference to the previously invoked value,
+    /// or with a snapshot of `value` if the event was not invoked before.
+    ///
+    /// ## Arguments
+    /// * `value` - The new value.
+    pub fn invoke(&self, value: T) {
+        let mut previous = self.previous.lock().unwrap();
+        let change = match previous.replace(value.clone()) {
+            None => Change::Snapshot(value),
+            Some(previous) => Change::Delta(value.diff(&previous)),
+        };
+        self.event.invoke(change);
+    }
+}
+
+impl<T> Default for DiffEvent<T>
+where
+    T: Diff + Clone,
+    T::Delta: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Position {
+        x: i32,
+        y: i32,
+    }
+
+    impl Diff for Position {
+        type Delta = (i32, i32);
+
+        fn diff(&self, previous: &Self) -> Self::Delta {
+            (self.x - previous.x, self.y - previous.y)
+        }
+    }
+
+    #[test]
+    fn second_invocation_delivers_delta() {
+        let event = DiffEvent::new();
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let changes = changes.clone();
+            event.add_fn_delta(move |change| changes.lock().unwrap().push(change))
+        }
+        .unwrap();
+
+        event.invoke(Position { x: 1, y: 2 });
+        event.invoke(Position { x: 4, y: 0 });
+
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![
+                Change::Snapshot(Position { x: 1, y: 2 }),
+                Change::Delta((3, -2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn late_handlers_receive_a_snapshot_first() {
+        let event = DiffEvent::new();
+        event.invoke(Position { x: 1, y: 2 });
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let changes = changes.clone();
+            event.add_fn_delta(move |change| changes.lock().unwrap().push(change))
+        }
+        .unwrap();
+        event.invoke(Position { x: 4, y: 0 });
+
+        assert_eq!(
+            *changes.lock().unwrap(),
+            vec![
+                Change::Snapshot(Position { x: 1, y: 2 }),
+                Change::Delta((3, -2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn concurrent_invocations_deliver_deltas_in_order() {
+        let event = Arc::new(DiffEvent::new());
+        let state = Arc::new(Mutex::new(None));
+        let _handle = {
+            let state = state.clone();
+            event.add_fn_delta(move |change| {
+                let mut state = state.lock().unwrap();
+                *state = match (change, state.take()) {
+                    (Change::Snapshot(value), _) => Some(value),
+                    (Change::Delta((dx, dy)), Some(Position { x, y })) => Some(Position {
+                        x: x + dx,
+                        y: y + dy,
+                    }),
+                    (Change::Delta(_), None) => panic!("delta without snapshot"),
+                };
+            })
+        }
+        .unwrap();
+
+        let threads: Vec<_> = (0..4)
+            .map(|thread| {
+                let event = event.clone();
+                std::thread::spawn(move || {
+                    for i in 0..100 {
+                        event.invoke(Position { x: thread, y: i });
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let last = event.previous.lock().unwrap().clone();
+        assert_eq!(*state.lock().unwrap(), last);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::EventHandle;
+
+/// Keeps handles of events with different argument types together and
+/// unregisters all of them when dropped or cleared.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::{Event, HandleGroup};
+///
+/// let clicked = Event::<()>::new();
+/// let resized = Event::<(u32, u32)>::new();
+///
+/// let mut group = HandleGroup::new();
+/// group.push(clicked.add_fn(|_| {}).unwrap());
+/// group.push(resized.add_fn(|_| {}).unwrap());
+/// assert_eq!(group.len(), 2);
+///
+/// drop(group);
+/// assert!(clicked.is_empty());
+/// assert!(resized.is_empty());
+/// ```
+#[derive(Default)]
+pub struct HandleGroup {
+    /// The type-erased handles; each one unregisters its handler when dropped.
+    handles: Vec<Box<dyn Send>>,
+}
+
+impl HandleGroup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a handle to the group, keeping its handler registered until the group
+    /// is dropped or cleared.
+    pub fn push<TEventArgs>(&mut self, handle: EventHandle<TEventArgs>)
+    where
+        EventHandle<TEventArgs>: Send + 'static,
+    {
+        self.handles.push(Box::new(handle));
+    }
+
+    /// Unregisters all handlers of the group.
+    pub fn clear(&mut self) {
+        self.handles.clear();
+    }
+
+    /// Returns the number of handles in the group.
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Determines whether the group holds no handles.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+}
+
+impl<TEventArgs> Extend<EventHandle<TEventArgs>> for HandleGroup
+where
+    EventHandle<TEventArgs>: Send + 'static,
+{
+    fn extend<I: IntoIterator<Item = EventHandle<TEventArgs>>>(&mut self, iter: I) {
+        for handle in iter {
+            self.push(handle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Event;
+
+    #[test]
+    fn dropping_the_group_unregisters_all_handlers() {
+        let numbers = Event::<i32>::new();
+        let names = Event::<String>::new();
+
+        let mut group = HandleGroup::new();
+        group.push(numbers.add_fn(|_| {}).unwrap());
+        group.push(names.add_fn(|_| {}).unwrap());
+        group.extend([numbers.add_fn(|_| {}).unwrap()]);
+        assert_eq!((numbers.len(), names.len()), (2, 1));
+
+        drop(group);
+        assert!(numbers.is_empty());
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn clear_unregisters_and_keeps_the_group_usable() {
+        let event = Event::<()>::new();
+        let mut group = HandleGroup::new();
+        group.push(event.add_fn(|_| {}).unwrap());
+
+        group.clear();
+        assert!(group.is_empty());
+        assert!(event.is_empty());
+
+        group.push(event.add_fn(|_| {}).unwrap());
+        assert_eq!(event.invoke(()), 1);
+    }
+}
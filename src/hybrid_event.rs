@@ -0,0 +1,177 @@
+use crate::{AddHandlerError, Event, EventHandle};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, ThreadId};
+
+/// An event combining thread-safe handlers with handlers bound to the thread
+/// that created the event.
+///
+/// Thread-safe handlers are called by every invocation. Thread-local handlers, which
+/// need not be `Send`, are only called by invocations on the owning thread and skipped
+/// otherwise.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::HybridEvent;
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// let event = HybridEvent::new();
+/// let local = Rc::new(Cell::new(0));
+/// event.add_local_fn({
+///     let local = local.clone();
+///     move |value: i32| local.set(value)
+/// }).unwrap();
+///
+/// assert_eq!(event.invoke(7), 0);
+/// assert_eq!(local.get(), 7);
+/// ```
+pub struct HybridEvent<TEventArgs> {
+    event: Event<TEventArgs>,
+    /// The thread owning the thread-local handlers.
+    owner: ThreadId,
+    local: LocalHandlers<TEventArgs>,
+    /// The number of thread-local handlers, readable from any thread.
+    local_count: AtomicUsize,
+}
+
+type LocalHandler<TEventArgs> = Rc<dyn Fn(TEventArgs)>;
+
+/// Handlers that may only be accessed on the owning thread of their [`HybridEvent`].
+struct LocalHandlers<TEventArgs>(RefCell<Vec<LocalHandler<TEventArgs>>>);
+
+// SAFETY: The handlers are only accessed on the owning thread, which is checked before
+// every access. If the event is dropped on another thread, the handlers are leaked
+// instead of dropped.
+unsafe impl<TEventArgs> Send for LocalHandlers<TEventArgs> {}
+unsafe impl<TEventArgs> Sync for LocalHandlers<TEventArgs> {}
+
+impl<TEventArgs> HybridEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    /// Creates an event owned by the calling thread.
+    pub fn new() -> Self {
+        Self {
+            event: Event::new(),
+            owner: thread::current().id(),
+            local: LocalHandlers(RefCell::new(Vec::new())),
+            local_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Registers a thread-safe closure, called by invocations on any thread.
+    pub fn add_fn<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        self.event.add_fn(handler)
+    }
+
+    /// Registers a thread-local closure, called only by invocations on the owning thread.
+    ///
+    /// Thread-local handlers live as long as the event.
+    ///
+    /// ## Errors
+    /// Fails with [`AddHandlerError::NotOwningThread`] if called on another thread.
+    pub fn add_local_fn<T>(&self, handler: T) -> Result<(), AddHandlerError>
+    where
+        T: Fn(TEventArgs) + 'static,
+    {
+        if !self.is_owning_thread() {
+            return Err(AddHandlerError::NotOwningThread);
+        }
+        self.local.0.borrow_mut().push(Rc::new(handler));
+        self.local_count.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Determines whether the calling thread owns the thread-local handlers.
+    pub fn is_owning_thread(&self) -> bool {
+        thread::current().id() == self.owner
+    }
+
+    /// Invokes the thread-safe handlers and, on the owning thread, the thread-local handlers.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The number of thread-local handlers skipped because the calling thread
+    /// does not own them.
+    pub fn invoke(&self, args: TEventArgs) -> usize {
+        if !self.is_owning_thread() {
+            self.event.invoke(args);
+            return self.local_count.load(Ordering::Relaxed);
+        }
+
+        // Copied, so that handlers may register further thread-local handlers.
+        let local = self.local.0.borrow().clone();
+        self.event.invoke(args.clone());
+        for handler in local {
+            handler(args.clone());
+        }
+        0
+    }
+}
+
+impl<TEventArgs> Default for HybridEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<TEventArgs> Drop for HybridEvent<TEventArgs> {
+    fn drop(&mut self) {
+        if thread::current().id() != self.owner {
+            std::mem::forget(self.local.0.take());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+    use std::sync::Arc;
+
+    #[test]
+    fn local_handlers_only_fire_on_the_owning_thread() {
+        let event = HybridEvent::new();
+        let shared = Arc::new(AtomicUsize::new(0));
+        let local = Rc::new(Cell::new(0));
+
+        let _shared = {
+            let shared = shared.clone();
+            event.add_fn(move |_: ()| {
+                shared.fetch_add(1, Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+        event
+            .add_local_fn({
+                let local = local.clone();
+                move |_: ()| local.set(local.get() + 1)
+            })
+            .unwrap();
+
+        assert_eq!(event.invoke(()), 0);
+        assert_eq!((shared.load(Ordering::SeqCst), local.get()), (1, 1));
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                assert_eq!(event.invoke(()), 1);
+                assert_eq!(
+                    event.add_local_fn(|_: ()| {}),
+                    Err(AddHandlerError::NotOwningThread)
+                );
+            });
+        });
+        assert_eq!((shared.load(Ordering::SeqCst), local.get()), (2, 1));
+    }
+}
@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+use std::sync::{Arc, Condvar, Mutex};
+
+thread_local! {
+    /// The latch of the waiting invocation currently running on this thread.
+    static CURRENT_LATCH: RefCell<Option<Arc<Latch>>> = const { RefCell::new(None) };
+}
+
+/// A task handed to a [`Scheduler`](crate::Scheduler).
+type Task = Box<dyn FnOnce() + Send>;
+
+/// Counts the scheduled handler calls of an invocation that have not completed yet,
+/// see [`Event::invoke_and_wait`](crate::Event::invoke_and_wait).
+pub(crate) struct Latch {
+    pending: Mutex<usize>,
+    completed: Condvar,
+}
+
+impl Latch {
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(Self {
+            pending: Mutex::new(0),
+            completed: Condvar::new(),
+        })
+    }
+
+    /// Blocks until all tracked tasks completed.
+    pub(crate) fn wait(&self) {
+        let pending = self.pending.lock().unwrap();
+        drop(
+            self.completed
+                .wait_while(pending, |pending| *pending > 0)
+                .unwrap(),
+        );
+    }
+
+    fn count_down(&self) {
+        let mut pending = self.pending.lock().unwrap();
+        *pending -= 1;
+        if *pending == 0 {
+            self.completed.notify_all();
+        }
+    }
+}
+
+/// Counts the task towards the latch of the current thread, if any.
+///
+/// The task counts as completed when it was run or dropped.
+pub(crate) fn track(task: Task) -> Task {
+    let Some(latch) = CURRENT_LATCH.with(|current| current.borrow().clone()) else {
+        return task;
+    };
+    *latch.pending.lock().unwrap() += 1;
+    let countdown = Countdown(latch);
+    Box::new(move || {
+        let _countdown = countdown;
+        task()
+    })
+}
+
+/// Counts down the latch when dropped, i.e. also if the task panics.
+struct Countdown(Arc<Latch>);
+
+impl Drop for Countdown {
+    fn drop(&mut self) {
+        self.0.count_down();
+    }
+}
+
+/// Sets the latch for the current thread until the scope is dropped.
+pub(crate) struct LatchScope {
+    /// The latch to restore when leaving the scope.
+    previous: Option<Arc<Latch>>,
+}
+
+impl LatchScope {
+    pub(crate) fn enter(latch: Arc<Latch>) -> Self {
+        let previous = CURRENT_LATCH.with(|current| current.replace(Some(latch)));
+        Self { previous }
+    }
+}
+
+impl Drop for LatchScope {
+    fn drop(&mut self) {
+        CURRENT_LATCH.with(|current| *current.borrow_mut() = self.previous.take());
+    }
+}
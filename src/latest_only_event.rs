@@ -0,0 +1,158 @@
+use crate::{HandleKey, RegistrationId};
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+/// An event delivering only the latest arguments to asynchronous handlers that
+/// cannot keep up.
+///
+/// Each handler runs on its own task and owns a single-slot mailbox. Invocations
+/// overwrite the mailbox, so a handler still processing earlier arguments is
+/// handed only the newest arguments once it is done; intermediate ones are dropped.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::LatestOnlyEvent;
+///
+/// # tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap().block_on(async {
+/// let event = LatestOnlyEvent::new();
+/// let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+/// let _id = event.add_fn(move |value: i32| {
+///     let sender = sender.clone();
+///     async move { sender.send(value).unwrap() }
+/// });
+///
+/// assert_eq!(event.invoke(7), 1);
+/// assert_eq!(receiver.recv().await, Some(7));
+/// # });
+/// ```
+pub struct LatestOnlyEvent<TEventArgs> {
+    mailboxes: Mutex<BTreeMap<RegistrationId, watch::Sender<Option<TEventArgs>>>>,
+}
+
+impl<TEventArgs> LatestOnlyEvent<TEventArgs>
+where
+    TEventArgs: Clone + Send + Sync + 'static,
+{
+    pub fn new() -> Self {
+        Self {
+            mailboxes: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Registers an asynchronous closure, spawning the task that calls it.
+    ///
+    /// The task ends when the handler is removed or the event is dropped.
+    ///
+    /// ## Returns
+    /// The identifier to pass to [`LatestOnlyEvent::remove`].
+    ///
+    /// ## Panics
+    /// Panics if called outside of a Tokio runtime.
+    pub fn add_fn<F, Fut>(&self, handler: F) -> RegistrationId
+    where
+        F: Fn(TEventArgs) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let (sender, mut receiver) = watch::channel(None);
+        let id = RegistrationId(HandleKey::next_id());
+        tokio::spawn(async move {
+            while receiver.changed().await.is_ok() {
+                let args = receiver.borrow_and_update().clone();
+                if let Some(args) = args {
+                    handler(args).await;
+                }
+            }
+        });
+        self.mailboxes.lock().unwrap().insert(id, sender);
+        id
+    }
+
+    /// Unregisters the specified handler.
+    ///
+    /// Returns whether the handler was registered.
+    pub fn remove(&self, id: RegistrationId) -> bool {
+        self.mailboxes.lock().unwrap().remove(&id).is_some()
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.mailboxes.lock().unwrap().len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Places the arguments in the mailbox of every handler, replacing arguments
+    /// not yet delivered.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The number of handlers notified.
+    pub fn invoke(&self, args: TEventArgs) -> usize {
+        let mailboxes = self.mailboxes.lock().unwrap();
+        for mailbox in mailboxes.values() {
+            mailbox.send_replace(Some(args.clone()));
+        }
+        mailboxes.len()
+    }
+}
+
+impl<TEventArgs> Default for LatestOnlyEvent<TEventArgs>
+where
+    TEventArgs: Clone + Send + Sync + 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::{mpsc, Notify};
+
+    #[tokio::test]
+    async fn slow_handler_skips_to_the_latest_args() {
+        let event = LatestOnlyEvent::new();
+        let (started, mut started_rx) = mpsc::unbounded_channel();
+        let (finished, mut finished_rx) = mpsc::unbounded_channel();
+        let release = Arc::new(Notify::new());
+
+        event.add_fn({
+            let release = release.clone();
+            move |value: i32| {
+                let (started, finished) = (started.clone(), finished.clone());
+                let release = release.clone();
+                async move {
+                    started.send(value).unwrap();
+                    release.notified().await;
+                    finished.send(value).unwrap();
+                }
+            }
+        });
+
+        event.invoke(1);
+        assert_eq!(started_rx.recv().await, Some(1));
+        for value in 2..=4 {
+            assert_eq!(event.invoke(value), 1);
+        }
+
+        release.notify_one();
+        assert_eq!(finished_rx.recv().await, Some(1));
+        assert_eq!(started_rx.recv().await, Some(4));
+        release.notify_one();
+        assert_eq!(finished_rx.recv().await, Some(4));
+
+        // Dropping the event ends the task; no further arguments were delivered.
+        drop(event);
+        assert_eq!(started_rx.recv().await, None);
+    }
+}
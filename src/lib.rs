@@ -96,22 +96,143 @@
 #![allow(unsafe_code)]
 #![forbid(unused_must_use)]
 
+#[cfg(feature = "futures")]
+use futures::future::{BoxFuture, FutureExt};
+use std::any::{Any, TypeId};
 use std::cell::Cell;
-use std::collections::BTreeMap;
+use std::collections::{btree_map, hash_map, BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::error::Error;
-use std::fmt::{Display, Formatter};
-use std::hash::{Hash, Hasher};
-use std::ops::{Deref, DerefMut};
-use std::sync::{Arc, RwLock, Weak};
+use std::fmt::{Debug, Display, Formatter};
+#[cfg(feature = "futures")]
+use std::future::Future;
+use std::hash::Hash;
+use std::iter;
+use std::ops::{ControlFlow, Deref, DerefMut};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod accumulator;
+#[cfg(feature = "allocator")]
+mod allocator;
+#[cfg(feature = "tokio")]
+mod async_event;
+mod audit;
+mod balanced_event;
+mod batch_event;
+#[cfg(feature = "futures")]
+mod cancellation;
+mod deferred;
+mod delivery;
+mod depth;
+mod diff_event;
+mod handle_group;
+mod hybrid_event;
+mod latch;
+#[cfg(feature = "tokio")]
+mod latest_only_event;
+mod local_event;
+mod queue;
+mod returning_event;
+mod routed;
+mod scheduler;
+mod sender_event;
+mod split;
+mod state_event;
+mod static_event;
+mod sticky_event;
+#[cfg(feature = "futures")]
+mod subscription;
+mod trace;
+
+#[cfg(feature = "allocator")]
+pub use allocator::HandlerAllocator;
+#[cfg(feature = "tokio")]
+pub use async_event::AsyncEvent;
+pub use audit::AuditEntry;
+pub use balanced_event::BalancedEvent;
+pub use batch_event::BatchEvent;
+#[cfg(feature = "futures")]
+pub use cancellation::{CancellationToken, Cancelled};
+pub use deferred::InvokeContext;
+pub use delivery::{CloneEach, DeliveryEvent, DeliveryMode, MoveSingle, SharedArc};
+pub use diff_event::{Change, Diff, DiffEvent};
+pub use handle_group::HandleGroup;
+pub use hybrid_event::HybridEvent;
+#[cfg(feature = "tokio")]
+pub use latest_only_event::LatestOnlyEvent;
+pub use local_event::LocalEvent;
+pub use returning_event::ReturningEvent;
+pub use scheduler::Scheduler;
+pub use sender_event::SenderEvent;
+pub use split::{EventSender, EventSource};
+pub use state_event::StateEvent;
+pub use static_event::StaticEvent;
+pub use sticky_event::StickyEvent;
+pub use trace::UNTRACED;
 
 pub mod prelude {
-    pub use crate::{Event, EventHandle, EventInvocationError, Invoke};
+    pub use crate::{
+        AddHandlerError, Event, EventHandle, EventInvocationError, HandlerErrors, HandlerFailure,
+        HandlerId, HandlerKind, HandlerMeta, Invoke, InvokeStats, OrderPolicy,
+        PanicIsolationUnavailable, Reentrancy, RegistrationId, WeakEvent,
+    };
 }
 
 /// Alias for trivial function pointers.
-pub type FnEventHandlerDelegate<TEventArgs> = fn(TEventArgs) -> ();
+pub type FnEventHandlerDelegate<TEventArgs> = fn(TEventArgs);
+
+/// Alias for fallible argument preprocessors, with the error type erased.
+type TryPreprocessor<TEventArgs> =
+    dyn Fn(TEventArgs) -> Result<TEventArgs, Box<dyn Any + Send>> + Send + Sync;
+
+/// Alias for handing a handler call to a [`Scheduler`], with the scheduler's bounds erased.
+type ScheduleFn<TEventArgs> = dyn Fn(Arc<Registration<TEventArgs>>, TEventArgs) + Send + Sync;
+
+/// Alias for callbacks notified of unhandled routed invocations.
+type UnhandledCallback<TEventArgs> = dyn Fn(&TEventArgs) + Send + Sync;
+
+/// Alias for argument validators.
+type Validator<TEventArgs> = dyn Fn(&TEventArgs) -> bool + Send + Sync;
+
+/// Alias for user-defined handler orderings.
+type OrderComparator = dyn Fn(&HandlerMeta, &HandlerMeta) -> std::cmp::Ordering + Send;
+
+/// Alias for callbacks notified of registrations and removals.
+type LifecycleCallback = dyn Fn(RegistrationId) + Send + Sync;
+
+/// Alias for boxed one-shot handlers.
+#[cfg(not(feature = "no_fnonce"))]
+type BoxedFnOnceDelegate<TEventArgs> = Box<dyn FnOnce(TEventArgs) + Send>;
+
+/// Alias for boxed handlers taking the arguments by reference.
+type BoxedFnRefDelegate<TEventArgs> = Box<dyn Fn(&TEventArgs) + Send>;
+
+/// Alias for boxed mutable handlers.
+type BoxedFnMutDelegate<TEventArgs> = Box<dyn FnMut(TEventArgs) + Send>;
+
+/// Alias for boxed asynchronous handlers.
+#[cfg(feature = "futures")]
+type AsyncDelegate<TEventArgs> = Box<dyn Fn(TEventArgs) -> BoxFuture<'static, ()> + Send>;
+
+/// Alias for boxed mutable asynchronous handlers.
+#[cfg(feature = "futures")]
+type AsyncMutDelegate<TEventArgs> = Box<dyn FnMut(TEventArgs) -> BoxFuture<'static, ()> + Send>;
 
 /// An event registration.
+#[cfg_attr(
+    feature = "no_fnonce",
+    doc = r#"
+One-shot handlers are not available with the `no_fnonce` feature:
+
+```compile_fail
+let event = event_handler::Event::new();
+let _handle = event.add_fnonce(|_: ()| {});
+```
+"#
+)]
 pub struct Event<TEventArgs = ()> {
     handlers: Arc<MapLocked<TEventArgs>>,
 }
@@ -121,320 +242,6357 @@ unsafe impl<TEventArgs: Send + Sync> Sync for Event<TEventArgs> {}
 /// A concrete type of a handler.
 enum HandlerType<TEventArgs> {
     BoxedFn(Box<dyn Fn(TEventArgs) + Send>),
-    BoxedFnOnce(Cell<Option<Box<dyn FnOnce(TEventArgs) + Send>>>),
+    #[cfg(not(feature = "no_fnonce"))]
+    BoxedFnOnce(Mutex<Option<BoxedFnOnceDelegate<TEventArgs>>>),
+    /// A handler taking the arguments by reference.
+    BoxedFnRef(BoxedFnRefDelegate<TEventArgs>),
+    /// A mutable handler, locked while it is called.
+    BoxedFnMut(Mutex<BoxedFnMutDelegate<TEventArgs>>),
     Function(FnEventHandlerDelegate<TEventArgs>),
+    #[cfg(feature = "allocator")]
+    AllocatedFn(allocator::AllocatedFn<TEventArgs>),
+    /// An asynchronous handler; only called by asynchronous invocations.
+    #[cfg(feature = "futures")]
+    Async(AsyncDelegate<TEventArgs>),
+    /// A mutable asynchronous handler, locked while it creates its future.
+    #[cfg(feature = "futures")]
+    AsyncMut(Mutex<AsyncMutDelegate<TEventArgs>>),
 }
 
 unsafe impl<TEventArgs: Send + Sync> Sync for HandlerType<TEventArgs> {}
 
-/// Helper type declaration for a locked [`MapInner`].
-struct MapLocked<TEventArgs>(RwLock<MapInner<TEventArgs>>);
-
-/// The actual storage type.
-type MapInner<TEventArgs> = BTreeMap<HandleKey, HandlerType<TEventArgs>>;
+impl<TEventArgs> HandlerType<TEventArgs> {
+    /// Boxes a closure under a new key.
+    fn boxed_fn<T>(handler: T) -> (HandleKey, Self)
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let handler = Box::new(handler);
+        let key = HandleKey::next_id();
+        (key, HandlerType::BoxedFn(handler))
+    }
 
-/// A handle to a registration.
-/// When the handle is dropped, the registration is revoked.
-#[must_use = "This handle must be held alive for as long as the event should be used."]
-pub struct EventHandle<TEventArgs> {
-    /// The key in the map.
-    key: HandleKey,
-    /// Pointer to the map that (possibly) contains the key.
-    pointer: Weak<MapLocked<TEventArgs>>,
-}
+    /// Boxes a one-shot closure under a new key.
+    #[cfg(not(feature = "no_fnonce"))]
+    fn boxed_fnonce<T>(handler: T) -> (HandleKey, Self)
+    where
+        T: FnOnce(TEventArgs) + Send + 'static,
+    {
+        let handler = Box::new(handler);
+        let key = HandleKey::next_id();
+        (key, HandlerType::BoxedFnOnce(Mutex::new(Some(handler))))
+    }
 
-/// A key entry for a handler.
-#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone)]
-enum HandleKey {
-    PtrOfBox(usize),
-    FunctionPointer(usize),
-}
+    /// Boxes a mutable closure under a new key.
+    fn boxed_fnmut<T>(handler: T) -> (HandleKey, Self)
+    where
+        T: FnMut(TEventArgs) + Send + 'static,
+    {
+        let handler = Box::new(handler);
+        let key = HandleKey::next_id();
+        (key, HandlerType::BoxedFnMut(Mutex::new(handler)))
+    }
 
-/// Hashing for [`HandleKey`] instances.
-impl Hash for HandleKey {
-    fn hash<H: Hasher>(&self, state: &mut H) {
+    /// Calls the handler with the specified arguments.
+    /// One-shot handlers that were already consumed are skipped.
+    ///
+    /// Returns whether the handler was called.
+    fn call(&self, args: TEventArgs) -> bool {
         match self {
-            HandleKey::PtrOfBox(ptr) => {
-                let address = ptr as *const usize as usize;
-                address.hash(state)
+            HandlerType::Function(fun) => fun(args),
+            HandlerType::BoxedFn(fun) => fun(args),
+            HandlerType::BoxedFnRef(fun) => fun(&args),
+            HandlerType::BoxedFnMut(fun) => {
+                // Waiting for the lock from within the closure would deadlock,
+                // so nested invocations on the same thread skip it instead.
+                let address = fun as *const _ as usize;
+                if depth::current_depth(address) > 0 {
+                    return false;
+                }
+                let _running = depth::DepthScope::enter(address);
+                (fun.lock().unwrap_or_else(PoisonError::into_inner))(args)
             }
-            HandleKey::FunctionPointer(ptr) => {
-                let address = ptr as *const usize as usize;
-                address.hash(state)
+            #[cfg(feature = "allocator")]
+            HandlerType::AllocatedFn(fun) => fun.call(args),
+            #[cfg(feature = "futures")]
+            HandlerType::Async(_) | HandlerType::AsyncMut(_) => return false,
+            #[cfg(not(feature = "no_fnonce"))]
+            HandlerType::BoxedFnOnce(slot) => {
+                let fun = slot.lock().unwrap_or_else(PoisonError::into_inner).take();
+                match fun {
+                    Some(fun) => fun(args),
+                    None => return false,
+                }
             }
         }
+        true
     }
-}
 
-impl<TEventArgs> EventHandle<TEventArgs> {
-    /// Initializes a new `Handle` from a successful registration.
-    fn new(key: HandleKey, pointer: &Arc<MapLocked<TEventArgs>>) -> Self {
-        Self {
-            key,
-            pointer: Arc::downgrade(pointer),
+    /// Returns the kind of the handler.
+    fn kind(&self) -> HandlerKind {
+        match self {
+            HandlerType::BoxedFn(_) => HandlerKind::Fn,
+            #[cfg(feature = "allocator")]
+            HandlerType::AllocatedFn(_) => HandlerKind::Fn,
+            #[cfg(not(feature = "no_fnonce"))]
+            HandlerType::BoxedFnOnce(_) => HandlerKind::FnOnce,
+            HandlerType::BoxedFnRef(_) => HandlerKind::FnRef,
+            HandlerType::BoxedFnMut(_) => HandlerKind::FnMut,
+            HandlerType::Function(_) => HandlerKind::Function,
+            #[cfg(feature = "futures")]
+            HandlerType::Async(_) | HandlerType::AsyncMut(_) => HandlerKind::Async,
         }
     }
 
-    /// Determines whether the registration is still valid.
-    pub fn is_valid(&self) -> bool {
-        self.pointer.strong_count() > 0
+    /// Determines whether the handler is removed after being called.
+    #[cfg(not(feature = "no_fnonce"))]
+    fn is_once(&self) -> bool {
+        matches!(self, HandlerType::BoxedFnOnce(_))
     }
 
-    /// Invokes the event with the specified arguments.
+    /// Determines whether the handler is removed after being called,
+    /// which is never the case without one-shot handlers.
+    #[cfg(feature = "no_fnonce")]
+    fn is_once(&self) -> bool {
+        false
+    }
+
+    /// Calls the handler with the specified arguments, awaiting asynchronous handlers.
     ///
-    /// ## Arguments
-    /// * `args` - The event arguments to pass.
-    pub fn invoke(&self, args: TEventArgs) -> Result<(), EventInvocationError>
-    where
-        TEventArgs: Clone,
-    {
-        if let Some(ptr) = self.pointer.upgrade() {
-            ptr.invoke(args);
-            Ok(())
-        } else {
-            Err(EventInvocationError::EventDropped)
+    /// Returns whether the handler was called.
+    #[cfg(feature = "futures")]
+    async fn call_async(&self, args: TEventArgs) -> bool {
+        match self {
+            HandlerType::Async(fun) => {
+                fun(args).await;
+                true
+            }
+            HandlerType::AsyncMut(fun) => {
+                // The lock is released before awaiting, so the future may be polled elsewhere.
+                let future = (fun.lock().unwrap_or_else(PoisonError::into_inner))(args);
+                future.await;
+                true
+            }
+            _ => self.call(args),
         }
     }
 }
 
-#[derive(Debug, PartialEq)]
-pub enum EventInvocationError {
-    /// The event was dropped.
-    EventDropped,
+/// Helper type declaration for a locked [`MapInner`] and the event's configuration.
+struct MapLocked<TEventArgs> {
+    /// The registered handlers.
+    ///
+    /// Invocations clone the `Arc` and call the handlers without holding the lock,
+    /// so handlers may register and unregister handlers of the same event. Modifications
+    /// copy the map if an invocation is still using it.
+    map: RwLock<Arc<MapInner<TEventArgs>>>,
+    /// Whether further registrations are rejected.
+    frozen: AtomicBool,
+    /// Whether invocations call the handlers, see [`Event::set_enabled`].
+    enabled: AtomicBool,
+    /// The number of handlers required for the event to fire.
+    min_handlers: AtomicUsize,
+    /// The order in which the handlers are called.
+    order_policy: Mutex<OrderPolicy>,
+    /// Orders the handlers of an invocation instead of their priority, if set.
+    order_comparator: Mutex<Option<Box<OrderComparator>>>,
+    /// How invocations from within a handler are treated.
+    reentrancy: Mutex<Reentrancy>,
+    /// The arguments of the nested invocations deferred by [`Reentrancy::Queue`].
+    reentrant: Mutex<VecDeque<TEventArgs>>,
+    /// The number of invocations, used to rotate the first handler.
+    rotation: AtomicUsize,
+    /// The number of dispatched invocations, wrapping around to zero after `u64::MAX`.
+    invocations: AtomicU64,
+    /// The number of registrations, used to number them in order.
+    registrations: AtomicU64,
+    /// The number of handlers the event accepts.
+    max_handlers: AtomicUsize,
+    /// The stack size of threads running handlers, or `0` for the default.
+    handler_stack_size: AtomicUsize,
+    /// Called after a handler was registered.
+    on_subscribe: RwLock<Option<Arc<LifecycleCallback>>>,
+    /// Called after a handler was removed.
+    on_unsubscribe: RwLock<Option<Arc<LifecycleCallback>>>,
+    /// Called after a routed invocation that no handler marked as handled.
+    on_unhandled: RwLock<Option<Arc<UnhandledCallback<TEventArgs>>>>,
+    /// Called whenever a registration is rejected because of `max_handlers`.
+    on_overflow: Mutex<Option<Box<dyn Fn() + Send>>>,
+    /// Transforms or rejects the arguments before handlers are invoked.
+    preprocessor: RwLock<Option<Arc<TryPreprocessor<TEventArgs>>>>,
+    /// Rejects invalid arguments before handlers are invoked.
+    validator: RwLock<Option<Arc<Validator<TEventArgs>>>>,
+    /// Rejects arguments seen within the deduplication window.
+    dedup: RwLock<Option<Arc<Validator<TEventArgs>>>>,
+    /// Handles owned by the event, see [`Event::subscribe_into`];
+    /// one `BTreeMap<K, EventHandle<TEventArgs>>` per key type.
+    subscriptions: Mutex<HashMap<TypeId, Box<dyn Any + Send>>>,
+    /// The arguments of the currently running coalescing invocations.
+    in_flight: Mutex<HashSet<TEventArgs>>,
+    /// The feature flags consulted for flagged handlers.
+    flags: Option<Arc<RwLock<HashMap<String, bool>>>>,
+    /// Holds the handler calls until they are pumped, if set.
+    queue: Option<Mutex<queue::TaskQueue<TEventArgs>>>,
+    /// Runs the handler calls, if set; otherwise handlers are called inline.
+    scheduler: Option<Box<ScheduleFn<TEventArgs>>>,
+    /// The most recent invocations, if auditing is enabled.
+    audit: Option<Mutex<audit::AuditLog>>,
+    /// The allocator used for boxing closures.
+    #[cfg(feature = "allocator")]
+    allocator: Option<Arc<dyn HandlerAllocator>>,
+    /// The duration after which a handler is reported as slow.
+    #[cfg(feature = "log")]
+    slow_handler_threshold: Mutex<Option<Duration>>,
 }
 
-impl Display for EventInvocationError {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+/// The actual storage type.
+enum MapInner<TEventArgs> {
+    /// Handlers are stored, and invoked, in registration order.
+    Ordered(SequenceMap<TEventArgs>),
+    /// Handlers are stored in unspecified order, with faster insertion and removal.
+    Unordered(HashMap<HandleKey, Arc<Registration<TEventArgs>>>),
+}
+
+impl<TEventArgs> Clone for MapInner<TEventArgs> {
+    fn clone(&self) -> Self {
         match self {
-            EventInvocationError::EventDropped => write!(
-                f,
-                "Event could not be invoked because it was already dropped"
-            ),
+            Self::Ordered(map) => Self::Ordered(map.clone()),
+            Self::Unordered(map) => Self::Unordered(map.clone()),
         }
     }
 }
 
-impl Error for EventInvocationError {}
+impl<TEventArgs> MapInner<TEventArgs> {
+    fn new() -> Self {
+        Self::Ordered(SequenceMap::new())
+    }
 
-impl<TEventArgs> Drop for EventHandle<TEventArgs> {
-    fn drop(&mut self) {
-        if let Some(lock) = self.pointer.upgrade() {
-            let mut handlers = lock.write().unwrap();
-            handlers.remove(&self.key);
-        }
+    fn unordered() -> Self {
+        Self::Unordered(HashMap::new())
     }
-}
 
-impl<TEventArgs> Event<TEventArgs> {
-    pub fn new() -> Self
-    where
-        TEventArgs: Clone,
-    {
-        Self {
-            handlers: Arc::new(MapLocked::new(MapInner::new())),
+    /// Creates an empty map of the same kind.
+    fn empty_like(&self) -> Self {
+        match self {
+            Self::Ordered(_) => Self::new(),
+            Self::Unordered(_) => Self::unordered(),
         }
     }
 
-    pub fn add_fn<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, String>
-    where
-        T: Fn(TEventArgs) -> () + Send + 'static,
-    {
-        let handler = Box::new(handler);
-        let key = HandleKey::PtrOfBox((&*handler as *const _) as usize);
-        let mut handlers = self.handlers.write().unwrap();
-        let entry = HandlerType::BoxedFn(handler);
-        match handlers.insert(key, entry) {
-            None => Ok(EventHandle::new(key, &self.handlers)),
-            Some(_) => Err(String::from("The handler was already registered")),
+    fn len(&self) -> usize {
+        match self {
+            Self::Ordered(map) => map.len(),
+            Self::Unordered(map) => map.len(),
         }
     }
 
-    pub fn add_fnonce<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, String>
-    where
-        T: FnOnce(TEventArgs) -> () + Send + 'static,
-    {
-        let handler = Box::new(handler);
-        let key = HandleKey::PtrOfBox((&*handler as *const _) as usize);
-        let mut handlers = self.handlers.write().unwrap();
-        let entry = HandlerType::BoxedFnOnce(Cell::new(Some(handler)));
-        match handlers.insert(key, entry) {
-            None => Ok(EventHandle::new(key, &self.handlers)),
-            Some(_) => Err(String::from("The handler was already registered")),
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn contains_key(&self, key: &HandleKey) -> bool {
+        match self {
+            Self::Ordered(map) => map.contains_key(key),
+            Self::Unordered(map) => map.contains_key(key),
         }
     }
 
-    pub fn add_ptr(
+    fn get_key_value(
         &self,
-        handler: FnEventHandlerDelegate<TEventArgs>,
-    ) -> Result<EventHandle<TEventArgs>, String> {
-        let key = HandleKey::FunctionPointer((&handler as *const _) as usize);
-        let mut handlers = self.handlers.write().unwrap();
-        let entry = HandlerType::Function(handler);
-        match handlers.insert(key, entry) {
-            None => Ok(EventHandle::new(key, &self.handlers)),
-            Some(_) => Err(String::from("The handler was already registered")),
+        key: &HandleKey,
+    ) -> Option<(&HandleKey, &Arc<Registration<TEventArgs>>)> {
+        match self {
+            Self::Ordered(map) => map.get_key_value(key),
+            Self::Unordered(map) => map.get_key_value(key),
         }
     }
 
-    /// Returns the number of currently registered handlers.
-    pub fn len(&self) -> usize {
-        self.handlers.read().unwrap().len()
+    fn insert(&mut self, key: HandleKey, entry: Arc<Registration<TEventArgs>>) {
+        match self {
+            Self::Ordered(map) => map.insert(key, entry),
+            Self::Unordered(map) => {
+                map.insert(key, entry);
+            }
+        }
     }
 
-    /// Invokes the event.
-    ///
-    /// ## Arguments
-    /// * `args` - The event arguments.
-    pub fn invoke(&self, args: TEventArgs)
-    where
-        TEventArgs: Clone,
-    {
-        self.handlers.invoke(args)
+    fn remove(&mut self, key: &HandleKey) -> Option<Arc<Registration<TEventArgs>>> {
+        match self {
+            Self::Ordered(map) => map.remove(key),
+            Self::Unordered(map) => map.remove(key),
+        }
+    }
+
+    fn iter(&self) -> MapIter<'_, TEventArgs> {
+        match self {
+            Self::Ordered(map) => MapIter::Ordered(map.entries.values()),
+            Self::Unordered(map) => MapIter::Unordered(map.iter()),
+        }
     }
 }
 
-impl Default for Event {
-    fn default() -> Self {
-        Self::new()
+/// Handlers keyed by their registration order, with a secondary lookup by identity.
+struct SequenceMap<TEventArgs> {
+    /// The handlers, keyed by [`Registration::sequence`].
+    entries: BTreeMap<u64, (HandleKey, Arc<Registration<TEventArgs>>)>,
+    /// The sequence number of each handler, used to reject duplicate registrations.
+    sequences: HashMap<HandleKey, u64>,
+}
+
+impl<TEventArgs> Clone for SequenceMap<TEventArgs> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+            sequences: self.sequences.clone(),
+        }
     }
 }
 
-impl<TEventArgs> MapLocked<TEventArgs>
-where
-    TEventArgs: Clone,
-{
-    const fn new(inner: MapInner<TEventArgs>) -> Self {
-        Self(RwLock::new(inner))
+impl<TEventArgs> SequenceMap<TEventArgs> {
+    fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            sequences: HashMap::new(),
+        }
     }
 
-    fn invoke(&self, args: TEventArgs) {
-        let mut unregister_list = Vec::new();
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
 
-        {
-            let handlers = self.read().unwrap();
-            for (key, entry) in handlers.iter() {
-                let args = args.clone();
-                match &entry {
-                    HandlerType::Function(fun) => fun(args),
-                    HandlerType::BoxedFn(fun) => fun(args),
-                    HandlerType::BoxedFnOnce(cell) => {
-                        let fun = cell.replace(None);
-                        if fun.is_some() {
-                            (fun.unwrap())(args);
-                        }
-                        unregister_list.push(key.clone());
-                    }
-                }
-            }
-        }
+    fn contains_key(&self, key: &HandleKey) -> bool {
+        self.sequences.contains_key(key)
+    }
 
-        // Clean up after any FnOnce type.
-        if !unregister_list.is_empty() {
-            let mut handlers = self.write().unwrap();
-            for key in unregister_list {
-                handlers.remove(&key);
-            }
+    fn get_key_value(
+        &self,
+        key: &HandleKey,
+    ) -> Option<(&HandleKey, &Arc<Registration<TEventArgs>>)> {
+        let (key, entry) = self.entries.get(self.sequences.get(key)?)?;
+        Some((key, entry))
+    }
+
+    /// Inserts the handler at the position of its sequence number,
+    /// replacing any handler registered under the same key.
+    fn insert(&mut self, key: HandleKey, entry: Arc<Registration<TEventArgs>>) {
+        if let Some(previous) = self.sequences.insert(key, entry.sequence) {
+            self.entries.remove(&previous);
         }
+        self.entries.insert(entry.sequence, (key, entry));
+    }
+
+    fn remove(&mut self, key: &HandleKey) -> Option<Arc<Registration<TEventArgs>>> {
+        let sequence = self.sequences.remove(key)?;
+        self.entries.remove(&sequence).map(|(_, entry)| entry)
     }
 }
 
-impl<TEventArgs> Deref for MapLocked<TEventArgs> {
-    type Target = RwLock<MapInner<TEventArgs>>;
+/// An iterator over the entries of a [`MapInner`].
+enum MapIter<'a, TEventArgs> {
+    Ordered(btree_map::Values<'a, u64, (HandleKey, Arc<Registration<TEventArgs>>)>),
+    Unordered(hash_map::Iter<'a, HandleKey, Arc<Registration<TEventArgs>>>),
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+impl<'a, TEventArgs> Iterator for MapIter<'a, TEventArgs> {
+    type Item = (&'a HandleKey, &'a Arc<Registration<TEventArgs>>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            MapIter::Ordered(iter) => iter.next().map(|(key, entry)| (key, entry)),
+            MapIter::Unordered(iter) => iter.next(),
+        }
     }
 }
 
-impl<TEventArgs> DerefMut for MapLocked<TEventArgs> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+/// An entry of a [`MapInner`].
+type MapEntry<'a, TEventArgs> = (&'a HandleKey, &'a Arc<Registration<TEventArgs>>);
+
+/// An iterator over the handlers of an invocation, in calling order.
+enum OrderedIter<'a, TEventArgs> {
+    /// The storage order, starting at an offset.
+    Rotated(iter::Chain<iter::Skip<MapIter<'a, TEventArgs>>, iter::Take<MapIter<'a, TEventArgs>>>),
+    /// Sorted by priority.
+    Sorted(std::vec::IntoIter<MapEntry<'a, TEventArgs>>),
+}
+
+impl<'a, TEventArgs> Iterator for OrderedIter<'a, TEventArgs> {
+    type Item = MapEntry<'a, TEventArgs>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            OrderedIter::Rotated(iter) => iter.next(),
+            OrderedIter::Sorted(iter) => iter.next(),
+        }
     }
 }
 
-/// Provides the `invoke` function for an event.
-pub trait Invoke<TEventArgs>
-where
-    TEventArgs: Clone,
-{
-    fn invoke(&self, args: TEventArgs);
+/// A registered handler and its metadata.
+struct Registration<TEventArgs> {
+    /// The handler to call.
+    handler: HandlerType<TEventArgs>,
+    /// The group the handler belongs to.
+    tag: Option<u32>,
+    /// The topic the handler is registered for, see [`Event::invoke_topic`].
+    topic: Option<String>,
+    /// The handler that must run before this one, see [`Event::invoke_topological`].
+    after: Mutex<Option<HandleKey>>,
+    /// The feature flag that must be enabled for the handler to fire, see [`Event::with_flags`].
+    flag: Option<String>,
+    /// Fire only on every n-th invocation, see [`Event::add_fn_every`].
+    every: Option<usize>,
+    /// The priority of the handler; lower values are called first.
+    priority: i32,
+    /// The order of the registration among all registrations of the event.
+    sequence: u64,
+    /// Rejects registering the same handler again, see [`Event::add_ptr_unique`].
+    unique: Option<UniqueKey>,
+    /// Whether the handler is skipped while registered, see [`Event::set_blocked`].
+    blocked: AtomicBool,
+    /// Whether [`Event::invoke_async_cancellable`] may abort the handler mid-flight.
+    #[cfg(feature = "futures")]
+    cancellable: bool,
+    /// The call stack of the registration.
+    #[cfg(feature = "backtrace")]
+    site: std::backtrace::Backtrace,
 }
 
-impl<TEventArgs> Invoke<TEventArgs> for Event<TEventArgs>
-where
-    TEventArgs: Clone,
-{
-    fn invoke(&self, args: TEventArgs) {
-        self.invoke(args)
+impl<TEventArgs> Registration<TEventArgs> {
+    fn new(handler: HandlerType<TEventArgs>) -> Self {
+        Self {
+            handler,
+            tag: None,
+            topic: None,
+            after: Mutex::new(None),
+            flag: None,
+            every: None,
+            priority: 0,
+            sequence: 0,
+            unique: None,
+            blocked: AtomicBool::new(false),
+            #[cfg(feature = "futures")]
+            cancellable: false,
+            #[cfg(feature = "backtrace")]
+            site: std::backtrace::Backtrace::force_capture(),
+        }
     }
-}
 
-impl<TEventArgs> Invoke<TEventArgs> for EventHandle<TEventArgs>
-where
-    TEventArgs: Clone,
-{
-    fn invoke(&self, args: TEventArgs) {
-        self.invoke(args).ok();
+    /// Rejects registering the handler again while this registration exists.
+    fn with_unique(mut self, unique: UniqueKey) -> Self {
+        self.unique = Some(unique);
+        self
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Assigns the registration to a group.
+    fn with_tag(mut self, tag: u32) -> Self {
+        self.tag = Some(tag);
+        self
+    }
 
-    fn dummy(_args: ()) {
-        println!("Dummy called.");
+    /// Registers the handler for a topic.
+    fn with_topic(mut self, topic: String) -> Self {
+        self.topic = Some(topic);
+        self
     }
 
-    #[test]
-    fn new_handler_has_no_registrations() {
-        let handler = Event::<()>::new();
-        assert_eq!(handler.len(), 0);
+    /// Makes the registration conditional on a feature flag.
+    fn with_flag(mut self, flag: String) -> Self {
+        self.flag = Some(flag);
+        self
     }
 
-    #[test]
-    #[allow(unused_variables)]
-    fn can_add_fn() {
-        let handler = Event::<()>::new();
-        let handle = handler.add_fn(dummy).unwrap();
-        assert_eq!(handler.len(), 1);
-        handler.invoke(());
+    /// Makes the registration fire only on every n-th invocation.
+    fn with_every(mut self, n: usize) -> Self {
+        self.every = Some(n.max(1));
+        self
     }
 
-    #[test]
-    #[allow(unused_variables)]
-    fn can_add_fnonce() {
-        let handler = Event::new();
-        let handle = handler.add_fnonce(dummy).unwrap();
-        assert_eq!(handler.len(), 1);
-        handler.invoke(());
-        assert_eq!(handler.len(), 0);
+    /// Declares that the registration must run after the specified one.
+    fn with_after(mut self, after: HandleKey) -> Self {
+        *self.after.get_mut().unwrap() = Some(after);
+        self
     }
 
-    #[test]
-    #[allow(unused_variables)]
-    fn can_add_function_pointer() {
-        let handler = Event::<()>::new();
-        let handle = handler.add_ptr(dummy).unwrap();
-        assert_eq!(handler.len(), 1);
-        handler.invoke(());
+    /// Returns the registration that must run before this one, if any.
+    fn after(&self) -> Option<HandleKey> {
+        *self.after.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
-    #[test]
-    #[allow(unused_variables)]
-    fn cannot_register_same_function_twice() {
-        let handler = Event::new();
-        let handle = handler.add_ptr(dummy).unwrap();
-        assert!(handler.add_ptr(dummy).is_err());
+    /// Assigns a priority to the registration.
+    fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
     }
 
-    #[test]
-    fn can_remove_handlers() {
-        let handler = Event::new();
-        let handle = handler.add_fn(dummy).unwrap();
-        assert_eq!(handler.len(), 1);
-        drop(handle);
-        assert_eq!(handler.len(), 0);
+    /// Describes the registration stored under the specified key.
+    fn meta(&self, key: HandleKey) -> HandlerMeta {
+        HandlerMeta {
+            id: RegistrationId(key),
+            tag: self.tag,
+            priority: self.priority,
+            kind: self.handler.kind(),
+        }
+    }
+}
+
+/// A handle to a registration.
+/// When the handle is dropped, the registration is revoked.
+#[must_use = "This handle must be held alive for as long as the event should be used."]
+pub struct EventHandle<TEventArgs> {
+    /// The key in the map.
+    key: HandleKey,
+    /// Pointer to the map that (possibly) contains the key.
+    pointer: Weak<MapLocked<TEventArgs>>,
+}
+
+/// A weak reference to an [`Event`] that does not keep the event alive.
+pub struct WeakEvent<TEventArgs> {
+    /// Pointer to the map of the event.
+    pointer: Weak<MapLocked<TEventArgs>>,
+}
+
+impl<TEventArgs> WeakEvent<TEventArgs> {
+    /// Determines whether the event still exists.
+    pub fn is_alive(&self) -> bool {
+        self.pointer.strong_count() > 0
+    }
+
+    /// Returns the number of nested invocations of the event currently running
+    /// on the calling thread; see [`Event::current_depth`].
+    pub fn current_depth(&self) -> usize {
+        depth::current_depth(self.pointer.as_ptr() as usize)
+    }
+
+    /// Invokes the event with the specified arguments, if it still exists.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments to pass.
+    ///
+    /// ## Errors
+    /// See [`Event::try_invoke`]; additionally fails with [`EventInvocationError::EventDropped`]
+    /// if the event no longer exists.
+    pub fn invoke(&self, args: TEventArgs) -> Result<(), EventInvocationError>
+    where
+        TEventArgs: Clone,
+    {
+        if let Some(ptr) = self.pointer.upgrade() {
+            ptr.try_invoke(args).map(|_| ())
+        } else {
+            Err(EventInvocationError::EventDropped)
+        }
+    }
+}
+
+impl<TEventArgs> Clone for WeakEvent<TEventArgs> {
+    fn clone(&self) -> Self {
+        Self {
+            pointer: self.pointer.clone(),
+        }
+    }
+}
+
+/// The order in which [`Event::invoke`] calls the handlers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrderPolicy {
+    /// Handlers are called in the order of the event's storage.
+    #[default]
+    Sequential,
+    /// Handlers are called in the order of the event's storage, starting one
+    /// handler later on every invocation and wrapping around, so that no
+    /// handler is always called first.
+    RotatingStart,
+}
+
+/// How [`Event::invoke`] and [`Event::try_invoke`] treat invocations from within
+/// a handler of the same event on the same thread, see [`Event::set_reentrancy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reentrancy {
+    /// Nested invocations call the handlers immediately.
+    #[default]
+    Allow,
+    /// Nested invocations do nothing.
+    Ignore,
+    /// Nested invocations do nothing; [`Event::try_invoke`] fails with
+    /// [`EventInvocationError::Reentrant`].
+    Error,
+    /// Nested invocations are deferred until the outermost invocation completed.
+    Queue,
+}
+
+/// The kind of a registered handler, see [`HandlerMeta`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HandlerKind {
+    /// A closure, see [`Event::add_fn`].
+    Fn,
+    /// A one-shot closure, see [`Event::add_fnonce`].
+    FnOnce,
+    /// A closure taking the arguments by reference, see [`Event::add_fn_ref`].
+    FnRef,
+    /// A mutable closure, see [`Event::add_fnmut`].
+    FnMut,
+    /// A function pointer, see [`Event::add_ptr`].
+    Function,
+    /// An asynchronous closure.
+    Async,
+}
+
+/// The metadata of a registered handler, see [`Event::set_order_comparator`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandlerMeta {
+    /// The registration of the handler.
+    pub id: RegistrationId,
+    /// The group of the handler, see [`Event::add_fn_tagged`].
+    pub tag: Option<u32>,
+    /// The priority of the handler, see [`Event::add_fn_with_priority`].
+    pub priority: i32,
+    /// The kind of the handler.
+    pub kind: HandlerKind,
+}
+
+/// Describes how an invocation was executed, see [`Event::invoke_with_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InvokeStats {
+    /// The number of handlers called.
+    pub fired: usize,
+    /// Whether the invocation returned before considering any handler, e.g.
+    /// because no handlers were registered or the arguments were rejected.
+    pub short_circuited: bool,
+    /// The number of times the arguments were cloned.
+    pub cloned_args: usize,
+}
+
+/// A handler that panicked during [`Event::invoke_panic_safe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerFailure {
+    /// The registration of the handler.
+    pub id: RegistrationId,
+    /// The panic message.
+    pub message: String,
+}
+
+/// Describes how the handlers were spread over the workers, see [`Event::invoke_work_stealing`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WorkStealingStats {
+    /// The number of handlers each worker called.
+    pub called: Vec<usize>,
+    /// The handlers that panicked.
+    pub failures: Vec<HandlerFailure>,
+}
+
+/// Panics of handlers cannot be caught because the program was built with `panic = "abort"`;
+/// see [`Event::invoke_safe_checked`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PanicIsolationUnavailable;
+
+impl Display for PanicIsolationUnavailable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Handler panics cannot be isolated because panics abort the process"
+        )
+    }
+}
+
+impl Error for PanicIsolationUnavailable {}
+
+/// The errors of the handlers that failed during [`Event::invoke_try_all`],
+/// in the order the handlers were called.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandlerErrors<E> {
+    errors: Vec<(RegistrationId, E)>,
+}
+
+impl<E> HandlerErrors<E> {
+    /// Returns the failed registrations and their errors.
+    pub fn errors(&self) -> &[(RegistrationId, E)] {
+        &self.errors
+    }
+
+    /// Returns the failed registrations and their errors, consuming the aggregate.
+    pub fn into_errors(self) -> Vec<(RegistrationId, E)> {
+        self.errors
+    }
+}
+
+impl<E: Display> Display for HandlerErrors<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} event handler(s) failed", self.errors.len())?;
+        if let Some((_, first)) = self.errors.first() {
+            write!(f, ", first with: {first}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Error + 'static> Error for HandlerErrors<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.errors.first().map(|(_, error)| error as &dyn Error)
+    }
+}
+
+/// An opaque identifier of a handler registration.
+///
+/// The identifier is stable for as long as the registration exists.
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+pub struct RegistrationId(HandleKey);
+
+/// The identifier of a handler registration, see [`Event::remove`].
+pub type HandlerId = RegistrationId;
+
+/// A key entry for a handler.
+#[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Copy, Clone, Hash)]
+enum HandleKey {
+    /// An identifier unique to the registration.
+    Id(u64),
+}
+
+/// Identifies a handler registered through [`Event::add_ptr_unique`] or
+/// [`Event::add_fn_unique`], so that it is not registered twice.
+enum UniqueKey {
+    FunctionPointer(usize),
+    /// The key supplied for a closure.
+    Key(Box<dyn DynKey>),
+}
+
+impl PartialEq for UniqueKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::FunctionPointer(a), Self::FunctionPointer(b)) => a == b,
+            (Self::Key(a), Self::Key(b)) => a.eq_key(b.as_any()),
+            _ => false,
+        }
+    }
+}
+
+/// A type-erased key of [`Event::add_fn_unique`]; keys of different types are never equal.
+trait DynKey: Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+
+    fn eq_key(&self, other: &dyn Any) -> bool;
+}
+
+impl<K> DynKey for K
+where
+    K: Eq + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn eq_key(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<K>() == Some(self)
+    }
+}
+
+/// Whether a handler stopped an invocation of [`Event::invoke_until`].
+struct Stopped(bool);
+
+impl HandleKey {
+    /// Creates a key distinct from all keys created before, so that registering
+    /// the same closure or function twice results in two registrations.
+    fn next_id() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        HandleKey::Id(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl<TEventArgs> EventHandle<TEventArgs> {
+    /// Initializes a new `Handle` from a successful registration.
+    fn new(key: HandleKey, pointer: &Arc<MapLocked<TEventArgs>>) -> Self {
+        Self {
+            key,
+            pointer: Arc::downgrade(pointer),
+        }
+    }
+
+    /// Returns the identifier of the registration.
+    pub fn id(&self) -> RegistrationId {
+        RegistrationId(self.key)
+    }
+
+    /// Blocks or unblocks the handler, see [`Event::set_blocked`].
+    ///
+    /// ## Returns
+    /// `true` if the handler is registered, `false` otherwise.
+    pub fn set_blocked(&self, blocked: bool) -> bool {
+        self.pointer
+            .upgrade()
+            .map_or(false, |lock| lock.set_blocked(&self.key, blocked))
+    }
+
+    /// Determines whether the registration is still valid.
+    pub fn is_valid(&self) -> bool {
+        self.pointer.strong_count() > 0
+    }
+
+    /// Determines whether the event has no handlers registered, which is also
+    /// the case if the event no longer exists.
+    pub fn is_empty(&self) -> bool {
+        self.pointer
+            .upgrade()
+            .map_or(true, |lock| lock.read_map().is_empty())
+    }
+
+    /// Revokes the registration, consuming the handle.
+    ///
+    /// ## Returns
+    /// `true` if the handler was removed, `false` if the event no longer exists
+    /// or the handler was already removed.
+    pub fn unregister(mut self) -> bool {
+        self.revoke()
+    }
+
+    /// Detaches the handle from the registration, consuming the handle.
+    ///
+    /// The handler stays registered until the event is dropped or the handler is
+    /// removed otherwise. Handlers registered this way cannot be unregistered
+    /// individually, so forgetting handles repeatedly grows the event without bound.
+    pub fn forget(mut self) {
+        self.pointer = Weak::new();
+    }
+
+    /// Removes the handler from the event, leaving the handle detached.
+    fn revoke(&mut self) -> bool {
+        let Some(lock) = std::mem::take(&mut self.pointer).upgrade() else {
+            return false;
+        };
+        if !lock.remove(&self.key) {
+            return false;
+        }
+        lock.notify(&lock.on_unsubscribe, [self.key]);
+        true
+    }
+
+    /// Invokes the event with the specified arguments.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments to pass.
+    ///
+    /// ## Errors
+    /// See [`Event::try_invoke`]; additionally fails with [`EventInvocationError::EventDropped`]
+    /// if the event no longer exists.
+    pub fn invoke(&self, args: TEventArgs) -> Result<(), EventInvocationError>
+    where
+        TEventArgs: Clone,
+    {
+        if let Some(ptr) = self.pointer.upgrade() {
+            ptr.try_invoke(args).map(|_| ())
+        } else {
+            Err(EventInvocationError::EventDropped)
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum EventInvocationError {
+    /// The event was dropped.
+    EventDropped,
+    /// The event was invoked, but no handlers were registered.
+    NoHandlers,
+    /// Fewer handlers than the configured minimum were registered.
+    BelowMinimum,
+    /// The declared handler dependencies form a cycle.
+    DependencyCycle,
+    /// The arguments were rejected by the validator.
+    InvalidArgs,
+    /// No handler with the requested registration was found.
+    NoSuchHandler,
+    /// The event was invoked from within one of its handlers, see [`Reentrancy::Error`].
+    Reentrant,
+}
+
+impl Display for EventInvocationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventInvocationError::EventDropped => write!(
+                f,
+                "Event could not be invoked because it was already dropped"
+            ),
+            EventInvocationError::NoHandlers => {
+                write!(f, "Event was invoked, but no handlers were registered")
+            }
+            EventInvocationError::BelowMinimum => write!(
+                f,
+                "Event was not invoked because fewer handlers than required were registered"
+            ),
+            EventInvocationError::DependencyCycle => write!(
+                f,
+                "Event was not invoked because the handler dependencies form a cycle"
+            ),
+            EventInvocationError::InvalidArgs => write!(
+                f,
+                "Event was not invoked because the arguments were rejected by the validator"
+            ),
+            EventInvocationError::NoSuchHandler => write!(
+                f,
+                "Event was not invoked because the requested handler was not registered"
+            ),
+            EventInvocationError::Reentrant => write!(
+                f,
+                "Event was not invoked because it was invoked from within one of its handlers"
+            ),
+        }
+    }
+}
+
+impl Error for EventInvocationError {}
+
+/// The preprocessor rejected the arguments of [`Event::invoke_try`].
+#[derive(Debug, PartialEq)]
+pub enum PreprocessError<E> {
+    /// The error of the preprocessor.
+    Rejected(E),
+    /// The preprocessor failed with an error that is not of the requested type.
+    OtherType,
+}
+
+impl<E: Display> Display for PreprocessError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreprocessError::Rejected(error) => {
+                write!(
+                    f,
+                    "Event was not invoked because preprocessing failed: {error}"
+                )
+            }
+            PreprocessError::OtherType => write!(
+                f,
+                "Event was not invoked because preprocessing failed with an unexpected error type"
+            ),
+        }
+    }
+}
+
+impl<E: Debug + Display> Error for PreprocessError<E> {}
+
+/// The reason a handler could not be added to an event.
+#[derive(Debug, PartialEq)]
+pub enum AddHandlerError {
+    /// The handler was already registered.
+    AlreadyRegistered,
+    /// The event was frozen and does not accept further handlers.
+    Frozen,
+    /// The event already holds the maximum number of handlers.
+    CapacityExceeded,
+    /// The handler may only be registered on the thread owning the event.
+    NotOwningThread,
+}
+
+impl Display for AddHandlerError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AddHandlerError::AlreadyRegistered => {
+                write!(f, "The handler was already registered")
+            }
+            AddHandlerError::Frozen => write!(
+                f,
+                "The handler could not be registered because the event is frozen"
+            ),
+            AddHandlerError::CapacityExceeded => write!(
+                f,
+                "The handler could not be registered because the event is at capacity"
+            ),
+            AddHandlerError::NotOwningThread => write!(
+                f,
+                "The handler could not be registered because the event is owned by another thread"
+            ),
+        }
+    }
+}
+
+impl Error for AddHandlerError {}
+
+/// The former name of [`AddHandlerError`].
+#[deprecated(note = "renamed to `AddHandlerError`")]
+pub type EventRegistrationError = AddHandlerError;
+
+impl<TEventArgs> Drop for EventHandle<TEventArgs> {
+    fn drop(&mut self) {
+        self.revoke();
+    }
+}
+
+impl<TEventArgs> Debug for EventHandle<TEventArgs> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventHandle")
+            .field("key", &self.key)
+            .field("valid", &self.is_valid())
+            .finish()
+    }
+}
+
+impl<TEventArgs> Event<TEventArgs> {
+    pub fn new() -> Self {
+        Self::ordered()
+    }
+
+    /// Creates an event that invokes its handlers in registration order.
+    ///
+    /// This is the storage used by [`Event::new`].
+    pub fn ordered() -> Self {
+        Self {
+            handlers: Arc::new(MapLocked::new(MapInner::new())),
+        }
+    }
+
+    /// Creates an event that stores its handlers in a hash map, trading a deterministic
+    /// invocation order for faster registration and removal.
+    pub fn unordered() -> Self {
+        Self {
+            handlers: Arc::new(MapLocked::new(MapInner::unordered())),
+        }
+    }
+
+    /// Creates an event that boxes closures registered via [`Event::add_fn`]
+    /// and [`Event::add_fn_tagged`] using the specified allocator.
+    ///
+    /// Other kinds of handlers are allocated by the global allocator.
+    #[cfg(feature = "allocator")]
+    pub fn with_allocator<A>(allocator: A) -> Self
+    where
+        A: HandlerAllocator + 'static,
+        TEventArgs: Clone,
+    {
+        let mut map = MapLocked::new(MapInner::new());
+        map.allocator = Some(Arc::new(allocator));
+        Self {
+            handlers: Arc::new(map),
+        }
+    }
+
+    /// Creates an event that records the last `capacity` invocations,
+    /// see [`Event::audit_log`].
+    pub fn with_audit_capacity(capacity: usize) -> Self
+    where
+        TEventArgs: Clone,
+    {
+        let mut map = MapLocked::new(MapInner::new());
+        map.audit = Some(Mutex::new(audit::AuditLog::new(capacity)));
+        Self {
+            handlers: Arc::new(map),
+        }
+    }
+
+    /// Creates an event that hands every handler call to the specified scheduler
+    /// instead of calling the handler inline.
+    ///
+    /// Scheduled calls count as fired, regardless of when they run.
+    pub fn with_scheduler<S>(scheduler: S) -> Self
+    where
+        S: Scheduler + 'static,
+        TEventArgs: Clone + Send + Sync + 'static,
+    {
+        let mut map = MapLocked::new(MapInner::new());
+        map.scheduler = Some(Box::new(
+            move |entry: Arc<Registration<TEventArgs>>, args| {
+                scheduler.schedule(latch::track(Box::new(move || {
+                    entry.handler.call(args);
+                })))
+            },
+        ));
+        Self {
+            handlers: Arc::new(map),
+        }
+    }
+
+    /// Creates an event whose handlers registered via [`Event::add_fn_flagged`]
+    /// only fire while their flag is enabled in the specified map.
+    ///
+    /// The map is consulted on every invocation, so flags can be toggled at runtime.
+    pub fn with_flags(flags: Arc<RwLock<HashMap<String, bool>>>) -> Self
+    where
+        TEventArgs: Clone,
+    {
+        let mut map = MapLocked::new(MapInner::new());
+        map.flags = Some(flags);
+        Self {
+            handlers: Arc::new(map),
+        }
+    }
+
+    /// Creates an event whose invocations queue the handler calls instead of
+    /// executing them; the calls are executed by [`Event::pump`].
+    ///
+    /// Queued calls are executed in ascending order of the handlers' priority,
+    /// see [`Event::add_fn_with_priority`], and in the order they were queued otherwise.
+    pub fn queued() -> Self
+    where
+        TEventArgs: Clone,
+    {
+        let mut map = MapLocked::new(MapInner::new());
+        map.queue = Some(Mutex::new(queue::TaskQueue::new()));
+        Self {
+            handlers: Arc::new(map),
+        }
+    }
+
+    pub fn add_fn<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = self.box_fn(handler);
+        self.register(key, Registration::new(handler))
+    }
+
+    /// Registers a closure that is called at most once and then unregistered.
+    ///
+    /// The closure and its captured state are dropped as soon as its call returns,
+    /// so the one-shot handlers consumed by an invocation are dropped in the order
+    /// they were called.
+    #[cfg(not(feature = "no_fnonce"))]
+    pub fn add_fnonce<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: FnOnce(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = HandlerType::boxed_fnonce(handler);
+        self.register(key, Registration::new(handler))
+    }
+
+    /// Registers a closure that may mutate its captured state.
+    ///
+    /// The closure is locked while it is called, so concurrent invocations call it
+    /// one at a time. Invocations raised from within the closure skip it, as it is
+    /// still running.
+    pub fn add_fnmut<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: FnMut(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = HandlerType::boxed_fnmut(handler);
+        self.register(key, Registration::new(handler))
+    }
+
+    /// Registers a closure that only fires for arguments accepted by the matcher,
+    /// receiving the value the matcher extracted.
+    ///
+    /// This is typically used to handle a single variant of an enum, with the matcher
+    /// returning the data of that variant.
+    pub fn add_fn_variant<V, M, T>(
+        &self,
+        matcher: M,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        M: Fn(TEventArgs) -> Option<V> + Send + 'static,
+        T: Fn(V) + Send + 'static,
+    {
+        self.add_fn(move |args| {
+            if let Some(value) = matcher(args) {
+                handler(value);
+            }
+        })
+    }
+
+    /// Registers a closure taking the arguments by reference.
+    ///
+    /// The closure is called by [`Event::invoke`] without cloning the arguments for it,
+    /// and by [`Event::invoke_ref`], which does not require the arguments to be `Clone`.
+    pub fn add_fn_ref<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(&TEventArgs) + Send + 'static,
+    {
+        let handler = Box::new(handler);
+        let key = HandleKey::next_id();
+        self.register(key, Registration::new(HandlerType::BoxedFnRef(handler)))
+    }
+
+    /// Registers a closure as part of the specified group.
+    ///
+    /// Tagged handlers are invoked by [`Event::invoke`] like any other handler, and
+    /// selectively by [`Event::invoke_tagged`].
+    pub fn add_fn_tagged<T>(
+        &self,
+        tag: u32,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = self.box_fn(handler);
+        self.register(key, Registration::new(handler).with_tag(tag))
+    }
+
+    /// Registers a closure for the specified topic.
+    ///
+    /// Topic handlers are invoked by [`Event::invoke`] like any other handler, and
+    /// selectively by [`Event::invoke_topic`].
+    pub fn add_fn_for_topic<T>(
+        &self,
+        topic: String,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = self.box_fn(handler);
+        self.register(key, Registration::new(handler).with_topic(topic))
+    }
+
+    /// Registers a closure with the specified priority; lower values are called first.
+    ///
+    /// Handlers registered without a priority have priority `0`. The priority orders
+    /// the handler calls of [`Event::invoke`] as well as the calls executed by [`Event::pump`].
+    /// Once priorities are assigned, handlers of equal priority are called in the order
+    /// they were registered.
+    pub fn add_fn_with_priority<T>(
+        &self,
+        handler: T,
+        priority: i32,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = self.box_fn(handler);
+        self.register(key, Registration::new(handler).with_priority(priority))
+    }
+
+    /// Registers a closure that only fires on every `n`-th invocation of the event,
+    /// counted from the creation of the event or the last [`Event::reset_sequence`];
+    /// an `n` of `0` is treated as `1`.
+    ///
+    /// The count wraps around to zero after `u64::MAX` invocations; zero counts
+    /// as a multiple of every `n`.
+    pub fn add_fn_every<T>(
+        &self,
+        n: usize,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = self.box_fn(handler);
+        self.register(key, Registration::new(handler).with_every(n))
+    }
+
+    /// Resets the invocation count, so that the next invocation counts as the first one.
+    pub fn reset_sequence(&self) {
+        self.handlers.invocations.store(0, Ordering::Relaxed);
+    }
+
+    /// Registers a closure that only fires while the specified feature flag is enabled.
+    ///
+    /// Flags missing from the map, or events not created by [`Event::with_flags`],
+    /// count as disabled.
+    pub fn add_fn_flagged<T>(
+        &self,
+        flag: impl Into<String>,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = self.box_fn(handler);
+        self.register(key, Registration::new(handler).with_flag(flag.into()))
+    }
+
+    /// Registers a closure that must run after the specified registration.
+    ///
+    /// The dependency is only honored by [`Event::invoke_topological`]; it is ignored
+    /// once the registration it refers to is removed. See [`Event::set_after`] to
+    /// change it later.
+    pub fn add_fn_after<T>(
+        &self,
+        after: RegistrationId,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = self.box_fn(handler);
+        self.register(key, Registration::new(handler).with_after(after.0))
+    }
+
+    /// Registers a one-shot closure as part of the specified group.
+    ///
+    /// See [`Event::add_fn_tagged`].
+    #[cfg(not(feature = "no_fnonce"))]
+    pub fn add_fnonce_tagged<T>(
+        &self,
+        tag: u32,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: FnOnce(TEventArgs) + Send + 'static,
+    {
+        let (key, handler) = HandlerType::boxed_fnonce(handler);
+        self.register(key, Registration::new(handler).with_tag(tag))
+    }
+
+    /// Registers a closure unless a closure with an equal key is already registered.
+    ///
+    /// Closures cannot be compared, so the key identifies the handler instead. Keys of
+    /// different types are never equal; a key can be reused once its registration is gone.
+    ///
+    /// ## Arguments
+    /// * `unique` - The key identifying the handler.
+    /// * `handler` - The closure to register.
+    ///
+    /// ## Errors
+    /// Fails with [`AddHandlerError::AlreadyRegistered`] if a closure with an equal key
+    /// was registered through this method before.
+    pub fn add_fn_unique<K, T>(
+        &self,
+        unique: K,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        K: Eq + Send + Sync + 'static,
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let unique = UniqueKey::Key(Box::new(unique));
+        let (key, handler) = self.box_fn(handler);
+        self.register(key, Registration::new(handler).with_unique(unique))
+    }
+
+    /// Registers a closure that receives a weak reference to this event.
+    ///
+    /// This allows handlers to re-raise the event without creating a reference cycle
+    /// between the event and the handler.
+    pub fn add_fn_with_weak_self<T>(
+        &self,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(WeakEvent<TEventArgs>, TEventArgs) + Send + 'static,
+        TEventArgs: Send + Sync + 'static,
+    {
+        let event = self.downgrade();
+        self.add_fn(move |args| handler(event.clone(), args))
+    }
+
+    /// Registers a closure that receives the context of the invocation,
+    /// allowing it to defer work until all handlers have run.
+    ///
+    /// See [`Event::invoke_deferred`].
+    pub fn add_fn_with_context<T>(
+        &self,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(&InvokeContext, TEventArgs) + Send + 'static,
+    {
+        self.add_fn(move |args| handler(&InvokeContext::new(), args))
+    }
+
+    /// Registers a closure that contributes to the accumulator passed to
+    /// [`Event::invoke_with_accumulator`].
+    ///
+    /// The handler is skipped by invocations without an accumulator of type `Acc`.
+    pub fn add_fn_acc<Acc, T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        Acc: 'static,
+        T: Fn(&mut Acc, TEventArgs) + Send + 'static,
+    {
+        let event = self.handlers.address();
+        self.add_fn(move |args| {
+            accumulator::with_current(event, |accumulator: &Mutex<Acc>| {
+                handler(&mut accumulator.lock().unwrap(), args)
+            });
+        })
+    }
+
+    /// Registers a closure that may stop the propagation of the event.
+    ///
+    /// Returning [`ControlFlow::Break`] prevents [`Event::invoke_until`] from calling
+    /// any further handlers; other invocations call the remaining handlers regardless.
+    pub fn add_fn_flow<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) -> ControlFlow<()> + Send + 'static,
+    {
+        let event = self.handlers.address();
+        self.add_fn(move |args| {
+            if handler(args).is_break() {
+                accumulator::with_current(event, |stopped: &Mutex<Stopped>| {
+                    stopped.lock().unwrap().0 = true
+                });
+            }
+        })
+    }
+
+    /// Registers a fallible closure.
+    ///
+    /// Errors are reported by [`Event::invoke_try_all`] and [`Event::try_invoke_first_err`]
+    /// if their error type is `E`, and discarded otherwise.
+    pub fn add_try_fn<E, T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        E: 'static,
+        T: Fn(TEventArgs) -> Result<(), E> + Send + 'static,
+    {
+        // The identifier is only known once the handler is registered.
+        let id = Arc::new(Mutex::new(None));
+        let event = self.handlers.address();
+        let handle = self.add_fn({
+            let id = id.clone();
+            move |args| {
+                let Err(error) = handler(args) else {
+                    return;
+                };
+                let Some(id) = *id.lock().unwrap() else {
+                    return;
+                };
+                accumulator::with_current(event, |errors: &Mutex<Vec<(RegistrationId, E)>>| {
+                    errors.lock().unwrap().push((id, error))
+                });
+            }
+        })?;
+        *id.lock().unwrap() = Some(handle.id());
+        Ok(handle)
+    }
+
+    /// Registers a closure whose handle is owned by the event under the specified key.
+    ///
+    /// The handler stays registered until it is removed via [`Event::unsubscribe_key`],
+    /// replaced by another subscription under the same key, or the event is dropped.
+    pub fn subscribe_into<K, T>(&self, key: K, handler: T) -> Result<(), AddHandlerError>
+    where
+        K: Ord + Send + 'static,
+        T: Fn(TEventArgs) + Send + 'static,
+        TEventArgs: Send + Sync + 'static,
+    {
+        let handle = self.add_fn(handler)?;
+        let replaced = self
+            .handlers
+            .subscriptions
+            .lock()
+            .unwrap()
+            .entry(TypeId::of::<K>())
+            .or_insert_with(|| Box::new(BTreeMap::<K, EventHandle<TEventArgs>>::new()))
+            .downcast_mut::<BTreeMap<K, EventHandle<TEventArgs>>>()
+            .expect("subscriptions are keyed by their type")
+            .insert(key, handle);
+        drop(replaced);
+        Ok(())
+    }
+
+    /// Registers a closure for the duration of `body` only.
+    ///
+    /// The handler is unregistered once `body` returns or panics.
+    ///
+    /// ## Returns
+    /// The result of `body`.
+    ///
+    /// ## Errors
+    /// Fails without running `body` if the handler could not be registered.
+    pub fn with_subscription<T, R, F>(&self, handler: T, body: F) -> Result<R, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+        F: FnOnce() -> R,
+    {
+        let _handle = self.add_fn(handler)?;
+        Ok(body())
+    }
+
+    /// Unregisters the handler subscribed under the specified key via [`Event::subscribe_into`].
+    ///
+    /// Returns whether a subscription was removed.
+    pub fn unsubscribe_key<K>(&self, key: &K) -> bool
+    where
+        K: Ord + 'static,
+        TEventArgs: 'static,
+    {
+        let removed = self
+            .handlers
+            .subscriptions
+            .lock()
+            .unwrap()
+            .get_mut(&TypeId::of::<K>())
+            .and_then(|map| map.downcast_mut::<BTreeMap<K, EventHandle<TEventArgs>>>())
+            .and_then(|map| map.remove(key));
+        removed.is_some()
+    }
+
+    /// Registers a closure that receives the trace identifier of the invocation.
+    ///
+    /// The trace identifier is set by [`Event::invoke_traced_id`] and carried along
+    /// when the invocation is forwarded to other events, e.g. via [`Event::forward_to`].
+    /// Untraced invocations pass [`UNTRACED`].
+    pub fn add_fn_traced<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(u128, TEventArgs) + Send + 'static,
+    {
+        self.add_fn(move |args| handler(trace::current_trace_id(), args))
+    }
+
+    /// Forwards all invocations of this event to another event.
+    ///
+    /// The forwarding does not keep the other event alive; dropping the returned
+    /// handle stops the forwarding.
+    pub fn forward_to(
+        &self,
+        other: &Event<TEventArgs>,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        TEventArgs: Clone + Send + Sync + 'static,
+    {
+        let target = other.downgrade();
+        self.add_fn(move |args| {
+            target.invoke(args).ok();
+        })
+    }
+
+    /// Forwards all invocations of this event to another event, like [`Event::forward_to`],
+    /// with the forwarding handler registered at the specified priority.
+    ///
+    /// A priority above that of this event's handlers forwards after they were called.
+    pub fn forward_to_with_priority(
+        &self,
+        other: &Event<TEventArgs>,
+        priority: i32,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        TEventArgs: Clone + Send + Sync + 'static,
+    {
+        let target = other.downgrade();
+        self.add_fn_with_priority(
+            move |args| {
+                target.invoke(args).ok();
+            },
+            priority,
+        )
+    }
+
+    /// Forwards all invocations of this event into a broadcast channel of the
+    /// specified capacity, returning its first receiver.
+    ///
+    /// Further receivers are obtained through [`Receiver::resubscribe`](tokio::sync::broadcast::Receiver::resubscribe).
+    /// The forwarding handler stays registered for as long as the event exists;
+    /// invocations without any receivers left are discarded.
+    ///
+    /// ## Panics
+    /// Panics if `capacity` is `0`.
+    #[cfg(feature = "tokio")]
+    pub fn broadcast_channel(
+        &self,
+        capacity: usize,
+    ) -> Result<tokio::sync::broadcast::Receiver<TEventArgs>, AddHandlerError>
+    where
+        TEventArgs: Clone + Send + 'static,
+    {
+        let (sender, receiver) = tokio::sync::broadcast::channel(capacity);
+        self.add_fn(move |args| {
+            sender.send(args).ok();
+        })?
+        .forget();
+        Ok(receiver)
+    }
+
+    /// Returns a future that resolves to the arguments of the next invocation.
+    ///
+    /// This is the asynchronous counterpart of a one-shot handler: the handler
+    /// backing the future is unregistered once the future completes or is dropped.
+    /// The future resolves to `None` if the event is dropped before it fires.
+    #[cfg(feature = "futures")]
+    pub fn next(&self) -> Result<impl Future<Output = Option<TEventArgs>>, AddHandlerError>
+    where
+        TEventArgs: Send + 'static,
+    {
+        let (sender, receiver) = futures::channel::oneshot::channel();
+        let sender = Mutex::new(Some(sender));
+        let handle = self.add_fn(move |args| {
+            if let Some(sender) = sender.lock().unwrap().take() {
+                sender.send(args).ok();
+            }
+        })?;
+        Ok(async move {
+            let _handle = handle;
+            receiver.await.ok()
+        })
+    }
+
+    /// Returns a stream yielding the arguments of every invocation.
+    ///
+    /// The arguments are buffered without bound until the stream is polled, so a slow
+    /// consumer never loses arguments; see [`Event::subscribe_stream_bounded`] to limit
+    /// the buffer. Dropping the stream unregisters the handler feeding it; the stream
+    /// ends once the event is dropped.
+    #[cfg(feature = "futures")]
+    pub fn subscribe_stream(
+        &self,
+    ) -> Result<impl futures::Stream<Item = TEventArgs>, AddHandlerError>
+    where
+        TEventArgs: Send + 'static,
+    {
+        let (sender, receiver) = futures::channel::mpsc::unbounded();
+        let handle = self.add_fn(move |args| {
+            sender.unbounded_send(args).ok();
+        })?;
+        Ok(subscription::Subscription::new(receiver, handle))
+    }
+
+    /// Returns a stream yielding the arguments of every invocation, like
+    /// [`Event::subscribe_stream`], buffering at most `capacity` arguments.
+    ///
+    /// Invocations never wait for the consumer: arguments of invocations made while
+    /// the buffer is full are dropped for this stream. A `capacity` of `0` is treated as `1`.
+    #[cfg(feature = "futures")]
+    pub fn subscribe_stream_bounded(
+        &self,
+        capacity: usize,
+    ) -> Result<impl futures::Stream<Item = TEventArgs>, AddHandlerError>
+    where
+        TEventArgs: Send + 'static,
+    {
+        // The channel holds one additional message per sender.
+        let (sender, receiver) = futures::channel::mpsc::channel(capacity.max(1) - 1);
+        let sender = Mutex::new(sender);
+        let handle = self.add_fn(move |args| {
+            sender.lock().unwrap().try_send(args).ok();
+        })?;
+        Ok(subscription::Subscription::new(receiver, handle))
+    }
+
+    /// Registers a mutable asynchronous closure.
+    ///
+    /// The closure is locked while it creates its future, but not while the future
+    /// is awaited. Asynchronous handlers are skipped by synchronous invocations;
+    /// they are called by [`Event::invoke_async`] and [`Event::invoke_async_ordered`].
+    #[cfg(feature = "futures")]
+    pub fn add_async_fn<F, Fut>(
+        &self,
+        mut handler: F,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        F: FnMut(TEventArgs) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler: AsyncMutDelegate<TEventArgs> = Box::new(move |args| handler(args).boxed());
+        let key = HandleKey::next_id();
+        self.register(
+            key,
+            Registration::new(HandlerType::AsyncMut(Mutex::new(handler))),
+        )
+    }
+
+    /// Registers an asynchronous closure with the specified priority.
+    ///
+    /// Asynchronous handlers are skipped by synchronous invocations; they are called by
+    /// [`Event::invoke_async_ordered`] in ascending order of their priority.
+    #[cfg(feature = "futures")]
+    pub fn add_async_with_priority<F, Fut>(
+        &self,
+        priority: i32,
+        handler: F,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        F: Fn(TEventArgs) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler: AsyncDelegate<TEventArgs> = Box::new(move |args| handler(args).boxed());
+        let key = HandleKey::next_id();
+        let registration = Registration::new(HandlerType::Async(handler)).with_priority(priority);
+        self.register(key, registration)
+    }
+
+    /// Registers an asynchronous closure that may be aborted mid-flight by
+    /// [`Event::invoke_async_cancellable`].
+    ///
+    /// Aborting drops the handler's future at its current suspension point.
+    #[cfg(feature = "futures")]
+    pub fn add_async_cancellable<F, Fut>(
+        &self,
+        handler: F,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        F: Fn(TEventArgs) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handler: AsyncDelegate<TEventArgs> = Box::new(move |args| handler(args).boxed());
+        let key = HandleKey::next_id();
+        let mut registration = Registration::new(HandlerType::Async(handler));
+        registration.cancellable = true;
+        self.register(key, registration)
+    }
+
+    /// Registers a function.
+    ///
+    /// The same function may be registered multiple times and is then called once
+    /// per registration; see [`Event::add_ptr_unique`] to reject duplicates.
+    pub fn add_ptr(
+        &self,
+        handler: FnEventHandlerDelegate<TEventArgs>,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError> {
+        let key = HandleKey::next_id();
+        self.register(key, Registration::new(HandlerType::Function(handler)))
+    }
+
+    /// Registers a function unless it is already registered.
+    ///
+    /// ## Errors
+    /// Fails with [`AddHandlerError::AlreadyRegistered`] if the function
+    /// was registered through this method before.
+    pub fn add_ptr_unique(
+        &self,
+        handler: FnEventHandlerDelegate<TEventArgs>,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError> {
+        let unique = UniqueKey::FunctionPointer(handler as usize);
+        let registration = Registration::new(HandlerType::Function(handler));
+        self.register(HandleKey::next_id(), registration.with_unique(unique))
+    }
+
+    /// Creates a weak reference to this event.
+    pub fn downgrade(&self) -> WeakEvent<TEventArgs> {
+        WeakEvent {
+            pointer: Arc::downgrade(&self.handlers),
+        }
+    }
+
+    /// Boxes a closure using the event's allocator, if any.
+    fn box_fn<T>(&self, handler: T) -> (HandleKey, HandlerType<TEventArgs>)
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        #[cfg(feature = "allocator")]
+        if let Some(allocator) = &self.handlers.allocator {
+            let handler = allocator::AllocatedFn::new(handler, allocator.clone());
+            let key = HandleKey::next_id();
+            return (key, HandlerType::AllocatedFn(handler));
+        }
+
+        HandlerType::boxed_fn(handler)
+    }
+
+    /// Registers a handler under the specified key.
+    fn register(
+        &self,
+        key: HandleKey,
+        mut registration: Registration<TEventArgs>,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError> {
+        let mut handlers = self.handlers.write_map();
+        if self.handlers.frozen.load(Ordering::Acquire) {
+            return Err(AddHandlerError::Frozen);
+        }
+
+        if let Some(unique) = &registration.unique {
+            let mut entries = handlers.iter();
+            if entries.any(|(_, entry)| entry.unique.as_ref() == Some(unique)) {
+                return Err(AddHandlerError::AlreadyRegistered);
+            }
+        }
+
+        if handlers.len() >= self.handlers.max_handlers.load(Ordering::Acquire) {
+            drop(handlers);
+            if let Some(on_overflow) = &*self.handlers.on_overflow.lock().unwrap() {
+                on_overflow();
+            }
+            return Err(AddHandlerError::CapacityExceeded);
+        }
+
+        registration.sequence = self.handlers.registrations.fetch_add(1, Ordering::Relaxed);
+        Arc::make_mut(&mut handlers).insert(key, Arc::new(registration));
+        drop(handlers);
+
+        self.handlers.notify(&self.handlers.on_subscribe, [key]);
+        Ok(EventHandle::new(key, &self.handlers))
+    }
+
+    /// Atomically replaces all handlers, provided the current number of handlers
+    /// equals `expected_len`.
+    ///
+    /// The replaced handlers are unregistered; the new handlers live as long as the event
+    /// and cannot be unregistered individually. Replacing is possible even if the event
+    /// is frozen.
+    ///
+    /// ## Arguments
+    /// * `expected_len` - The number of handlers expected to be registered.
+    /// * `new_handlers` - The handlers to register instead.
+    ///
+    /// ## Errors
+    /// Returns the actual number of handlers if it differs from `expected_len`.
+    pub fn try_replace_all(
+        &self,
+        expected_len: usize,
+        new_handlers: Vec<Box<dyn Fn(TEventArgs) + Send>>,
+    ) -> Result<(), usize> {
+        let mut handlers = self.handlers.write_map();
+        if handlers.len() != expected_len {
+            return Err(handlers.len());
+        }
+
+        let mut replacement = handlers.empty_like();
+        let mut added = Vec::with_capacity(new_handlers.len());
+        for handler in new_handlers {
+            let key = HandleKey::next_id();
+            let mut entry = Registration::new(HandlerType::BoxedFn(handler));
+            entry.sequence = self.handlers.registrations.fetch_add(1, Ordering::Relaxed);
+            replacement.insert(key, Arc::new(entry));
+            added.push(key);
+        }
+
+        let replaced = std::mem::replace(&mut *handlers, Arc::new(replacement));
+        drop(handlers);
+
+        let removed: Vec<_> = replaced.iter().map(|(key, _)| *key).collect();
+        drop(replaced);
+        self.handlers.notify(&self.handlers.on_unsubscribe, removed);
+        self.handlers.notify(&self.handlers.on_subscribe, added);
+        Ok(())
+    }
+
+    /// Unregisters the handler with the specified identifier, without requiring its handle.
+    ///
+    /// The handle of the handler stays valid; dropping it no longer has any effect.
+    ///
+    /// ## Returns
+    /// `true` if the handler was removed, `false` if it was not registered (anymore).
+    pub fn remove(&self, id: HandlerId) -> bool {
+        if !self.handlers.remove(&id.0) {
+            return false;
+        }
+        self.handlers.notify(&self.handlers.on_unsubscribe, [id.0]);
+        true
+    }
+
+    /// Unregisters all handlers for which the predicate returns `false`.
+    ///
+    /// The predicate is called while the handlers are locked for writing, so it must not
+    /// use the event. Outstanding handles of removed handlers stay valid; dropping them
+    /// no longer has any effect.
+    ///
+    /// ## Returns
+    /// The number of handlers removed.
+    pub fn retain<F>(&self, mut f: F) -> usize
+    where
+        F: FnMut(HandlerId, &HandlerKind) -> bool,
+    {
+        let mut handlers = self.handlers.write_map();
+        let removed: Vec<_> = handlers
+            .iter()
+            .filter(|(key, entry)| !f(RegistrationId(**key), &entry.handler.kind()))
+            .map(|(key, _)| *key)
+            .collect();
+        if removed.is_empty() {
+            return 0;
+        }
+
+        let map = Arc::make_mut(&mut handlers);
+        let entries: Vec<_> = removed.iter().filter_map(|key| map.remove(key)).collect();
+        drop(handlers);
+
+        // Drops the handlers without holding the lock, as they may use the event.
+        drop(entries);
+        let count = removed.len();
+        self.handlers.notify(&self.handlers.on_unsubscribe, removed);
+        count
+    }
+
+    /// Unregisters all handlers.
+    ///
+    /// Outstanding handles stay valid; dropping them no longer has any effect.
+    ///
+    /// ## Returns
+    /// The number of handlers removed.
+    pub fn clear(&self) -> usize {
+        let mut handlers = self.handlers.write_map();
+        let empty = handlers.empty_like();
+        let cleared = std::mem::replace(&mut *handlers, Arc::new(empty));
+        drop(handlers);
+
+        let removed: Vec<_> = cleared.iter().map(|(key, _)| *key).collect();
+        drop(cleared);
+        let count = removed.len();
+        self.handlers.notify(&self.handlers.on_unsubscribe, removed);
+        count
+    }
+
+    /// Freezes the event, rejecting any further registrations.
+    ///
+    /// The event can still be invoked, and existing handles still unregister
+    /// their handlers when dropped.
+    pub fn freeze(&self) {
+        self.handlers.frozen.store(true, Ordering::Release);
+    }
+
+    /// Determines whether the event was frozen.
+    pub fn is_frozen(&self) -> bool {
+        self.handlers.frozen.load(Ordering::Acquire)
+    }
+
+    /// Enables or disables the event without unregistering its handlers.
+    ///
+    /// Invocations of a disabled event return immediately without calling any
+    /// handlers; one-shot handlers are kept for the next invocation once the
+    /// event is enabled again. Events are enabled when created.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.handlers.enabled.store(enabled, Ordering::Release);
+    }
+
+    /// Determines whether invocations call the handlers, see [`Event::set_enabled`].
+    pub fn is_enabled(&self) -> bool {
+        self.handlers.is_enabled()
+    }
+
+    /// Sets the order in which [`Event::invoke`] and [`Event::try_invoke`] call the handlers.
+    pub fn set_order_policy(&self, policy: OrderPolicy) {
+        *self
+            .handlers
+            .order_policy
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = policy;
+    }
+
+    /// Sets how [`Event::invoke`] and [`Event::try_invoke`] treat invocations from
+    /// within a handler of this event on the same thread.
+    ///
+    /// Invocations deferred by [`Reentrancy::Queue`] run in order once the outermost
+    /// [`Event::invoke`] or [`Event::try_invoke`] completed.
+    pub fn set_reentrancy(&self, policy: Reentrancy) {
+        *self.handlers.reentrancy.lock().unwrap() = policy;
+    }
+
+    /// Sets a comparator that orders the handlers called by [`Event::invoke`] and
+    /// [`Event::try_invoke`], replacing the order by priority.
+    ///
+    /// Handlers the comparator considers equal are called in the order given by
+    /// the order policy; see [`Event::set_order_policy`].
+    pub fn set_order_comparator<F>(&self, comparator: F)
+    where
+        F: Fn(&HandlerMeta, &HandlerMeta) -> std::cmp::Ordering + Send + 'static,
+    {
+        *self
+            .handlers
+            .order_comparator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(Box::new(comparator));
+    }
+
+    /// Removes the comparator set by [`Event::set_order_comparator`].
+    pub fn clear_order_comparator(&self) {
+        *self
+            .handlers
+            .order_comparator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    /// Sets the number of handlers that must be registered for the event to fire.
+    ///
+    /// Invocations with fewer handlers do not call any handler and fail with
+    /// [`EventInvocationError::BelowMinimum`].
+    pub fn set_min_handlers(&self, count: usize) {
+        self.handlers.min_handlers.store(count, Ordering::Release);
+    }
+
+    /// Sets the maximum number of handlers the event accepts.
+    ///
+    /// Further registrations fail with [`AddHandlerError::CapacityExceeded`];
+    /// handlers that are already registered are kept.
+    pub fn set_max_handlers(&self, count: usize) {
+        self.handlers.max_handlers.store(count, Ordering::Release);
+    }
+
+    /// Sets the stack size of the threads spawned by [`Event::invoke_par_timeout`]
+    /// and [`Event::invoke_work_stealing`], for handlers that need more stack than
+    /// the platform default.
+    ///
+    /// ## Arguments
+    /// * `bytes` - The stack size, or `0` to use the default.
+    pub fn set_handler_stack_size(&self, bytes: usize) {
+        self.handlers
+            .handler_stack_size
+            .store(bytes, Ordering::Release);
+    }
+
+    /// Creates a builder for threads running handlers.
+    fn handler_thread(&self) -> thread::Builder {
+        match self.handlers.handler_stack_size.load(Ordering::Acquire) {
+            0 => thread::Builder::new(),
+            bytes => thread::Builder::new().stack_size(bytes),
+        }
+    }
+
+    /// Sets a callback that is called with the identifier of every handler
+    /// registered from now on.
+    ///
+    /// The callback is called after the handler lock was released, so it may use the event.
+    pub fn set_on_subscribe<F>(&self, on_subscribe: F)
+    where
+        F: Fn(RegistrationId) + Send + Sync + 'static,
+    {
+        *self.handlers.on_subscribe.write().unwrap() = Some(Arc::new(on_subscribe));
+    }
+
+    /// Sets a callback that is called with the identifier of every handler removed
+    /// from now on, whether its handle was dropped, it was replaced, or a one-shot
+    /// handler was consumed.
+    ///
+    /// The callback is called after the handler lock was released, so it may use the event.
+    pub fn set_on_unsubscribe<F>(&self, on_unsubscribe: F)
+    where
+        F: Fn(RegistrationId) + Send + Sync + 'static,
+    {
+        *self.handlers.on_unsubscribe.write().unwrap() = Some(Arc::new(on_unsubscribe));
+    }
+
+    /// Sets a callback that is called whenever a registration is rejected
+    /// because the event is at capacity; see [`Event::set_max_handlers`].
+    ///
+    /// The callback is called outside of the handler lock, but must not
+    /// replace itself.
+    pub fn set_on_overflow<F>(&self, on_overflow: F)
+    where
+        F: Fn() + Send + 'static,
+    {
+        *self.handlers.on_overflow.lock().unwrap() = Some(Box::new(on_overflow));
+    }
+
+    /// Returns the call stack at which the specified handler was registered.
+    ///
+    /// Useful for finding handlers that were never unregistered.
+    #[cfg(feature = "backtrace")]
+    pub fn registration_site(&self, id: RegistrationId) -> Option<String> {
+        let handlers = self.handlers.read_map();
+        handlers
+            .get_key_value(&id.0)
+            .map(|(_, entry)| entry.site.to_string())
+    }
+
+    /// Returns the most recent invocations, oldest first.
+    ///
+    /// The log is empty unless the event was created by [`Event::with_audit_capacity`].
+    pub fn audit_log(&self) -> Vec<AuditEntry> {
+        match &self.handlers.audit {
+            Some(log) => log.lock().unwrap().entries(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the number of nested invocations of this event currently running
+    /// on the calling thread.
+    ///
+    /// This is `0` outside of any invocation, `1` within a handler of a top-level
+    /// invocation, `2` within a handler of an invocation raised by a handler, and so on.
+    pub fn current_depth(&self) -> usize {
+        depth::current_depth(self.handlers.address())
+    }
+
+    /// Blocks or unblocks the handler with the specified identifier.
+    ///
+    /// A blocked handler stays registered but is skipped by invocations; one-shot
+    /// handlers are kept until they are called after being unblocked.
+    ///
+    /// ## Returns
+    /// `true` if the handler is registered, `false` otherwise.
+    pub fn set_blocked(&self, id: HandlerId, blocked: bool) -> bool {
+        self.handlers.set_blocked(&id.0, blocked)
+    }
+
+    /// Declares that the handler with the specified identifier must run after
+    /// the registration `after`, replacing any previous dependency.
+    ///
+    /// The dependency is only honored by [`Event::invoke_topological`]. Unlike with
+    /// [`Event::add_fn_after`], dependencies declared this way may form a cycle.
+    ///
+    /// ## Returns
+    /// `true` if the handler is registered, `false` otherwise.
+    pub fn set_after(&self, id: RegistrationId, after: RegistrationId) -> bool {
+        self.handlers.set_after(&id.0, after.0)
+    }
+
+    /// Determines whether the specified registration exists.
+    pub(crate) fn is_registered(&self, id: RegistrationId) -> bool {
+        self.handlers.read_map().contains_key(&id.0)
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.read_map().len()
+    }
+
+    /// Determines whether no handlers are registered, e.g. to skip computing
+    /// arguments nobody listens to.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.read_map().is_empty()
+    }
+
+    /// Invokes the event.
+    ///
+    /// No handlers are called if fewer than the minimum number of handlers
+    /// are registered; see [`Event::set_min_handlers`].
+    ///
+    /// Handlers are called without holding the event's lock, so they may drop handles
+    /// or register handlers. Such changes take effect with the next invocation.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The number of handlers called; one-shot handlers that already fired are not counted.
+    pub fn invoke(&self, args: TEventArgs) -> usize
+    where
+        TEventArgs: Clone,
+    {
+        self.handlers.invoke(args)
+    }
+
+    /// Invokes the event like [`Event::invoke`], then blocks until the handler calls
+    /// handed to the scheduler have completed; see [`Event::with_scheduler`].
+    ///
+    /// For events calling their handlers inline, this is equivalent to [`Event::invoke`].
+    /// Calls queued for [`Event::pump`] are not waited for.
+    ///
+    /// The scheduler must run the calls without the help of the invoking thread,
+    /// otherwise this method never returns.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    pub fn invoke_and_wait(&self, args: TEventArgs)
+    where
+        TEventArgs: Clone,
+    {
+        let latch = latch::Latch::new();
+        {
+            let _scope = latch::LatchScope::enter(latch.clone());
+            self.handlers.invoke(args);
+        }
+        latch.wait();
+    }
+
+    /// Invokes the handlers registered by [`Event::add_fn_ref`], passing them a
+    /// reference to the arguments; all other handlers are skipped.
+    ///
+    /// The handlers are called directly, even if the event queues or schedules
+    /// handler calls. No handlers are called if the arguments are rejected by the
+    /// validator or fewer than the minimum number of handlers are registered.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    pub fn invoke_ref(&self, args: &TEventArgs) {
+        if !self.handlers.is_enabled() || !self.handlers.is_valid(args) {
+            return;
+        }
+        let handlers = self.handlers.current();
+        if self.handlers.is_below_minimum(&handlers) {
+            return;
+        }
+
+        let _depth = self.handlers.enter();
+        let sequence = self.handlers.next_sequence();
+        for (_, entry) in self.handlers.ordered(&handlers) {
+            if let HandlerType::BoxedFnRef(handler) = &entry.handler {
+                if self.handlers.is_active(entry, sequence) {
+                    handler(args);
+                }
+            }
+        }
+    }
+
+    /// Invokes the event, then runs the closures deferred by the handlers
+    /// via [`InvokeContext::defer`] in the order they were deferred.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    pub fn invoke_deferred(&self, args: TEventArgs)
+    where
+        TEventArgs: Clone,
+    {
+        let _scope = deferred::DeferScope::enter();
+        self.handlers.invoke(args);
+    }
+
+    /// Invokes the event, allowing handlers registered via [`Event::add_fn_with_context`]
+    /// to mark the invocation as handled through [`InvokeContext::set_handled`].
+    ///
+    /// If no handler marked the invocation as handled, including when no handlers are
+    /// registered, the callback set by [`Event::set_on_unhandled`] is called.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// Whether a handler marked the invocation as handled.
+    pub fn invoke_routed(&self, args: TEventArgs) -> bool
+    where
+        TEventArgs: Clone,
+    {
+        let on_unhandled = self.handlers.on_unhandled.read().unwrap().clone();
+        let retained = on_unhandled.as_ref().map(|_| args.clone());
+
+        let handled = {
+            let scope = routed::RouteScope::enter();
+            self.handlers.invoke(args);
+            scope.is_handled()
+        };
+
+        if let (false, Some(on_unhandled), Some(args)) = (handled, on_unhandled, retained) {
+            on_unhandled(&args);
+        }
+        handled
+    }
+
+    /// Sets a callback that is called after an [`Event::invoke_routed`] call
+    /// that no handler marked as handled.
+    pub fn set_on_unhandled<F>(&self, on_unhandled: F)
+    where
+        F: Fn(&TEventArgs) + Send + Sync + 'static,
+    {
+        *self.handlers.on_unhandled.write().unwrap() = Some(Arc::new(on_unhandled));
+    }
+
+    /// Invokes the event unless an invocation with equal arguments is already in progress.
+    ///
+    /// This suppresses duplicate concurrent broadcasts of idempotent events.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// `true` if the handlers were invoked, `false` if the invocation was coalesced.
+    pub fn invoke_coalesce_inflight(&self, args: TEventArgs) -> bool
+    where
+        TEventArgs: Eq + Hash + Clone,
+    {
+        if !self.handlers.in_flight.lock().unwrap().insert(args.clone()) {
+            return false;
+        }
+
+        /// Removes the arguments from the in-flight set, even if a handler panics.
+        struct InFlight<'a, T: Eq + Hash>(&'a Mutex<HashSet<T>>, Option<T>);
+
+        impl<T: Eq + Hash> Drop for InFlight<'_, T> {
+            fn drop(&mut self) {
+                if let Some(args) = self.1.take() {
+                    self.0.lock().unwrap().remove(&args);
+                }
+            }
+        }
+
+        let _in_flight = InFlight(&self.handlers.in_flight, Some(args.clone()));
+        self.handlers.invoke(args);
+        true
+    }
+
+    /// Sets a fallible preprocessor that transforms the arguments of [`Event::invoke`]
+    /// and [`Event::invoke_try`] before they are passed to the handlers.
+    ///
+    /// If preprocessing fails, no handlers are called. [`Event::invoke_try`] returns the
+    /// error, while [`Event::invoke`] discards it.
+    pub fn set_try_preprocessor<F, E>(&self, preprocessor: F)
+    where
+        F: Fn(TEventArgs) -> Result<TEventArgs, E> + Send + Sync + 'static,
+        E: Send + 'static,
+    {
+        let preprocessor: Arc<TryPreprocessor<TEventArgs>> = Arc::new(move |args| {
+            preprocessor(args).map_err(|error| Box::new(error) as Box<dyn Any + Send>)
+        });
+        *self.handlers.preprocessor.write().unwrap() = Some(preprocessor);
+    }
+
+    /// Removes the preprocessor set by [`Event::set_try_preprocessor`].
+    pub fn clear_preprocessor(&self) {
+        *self.handlers.preprocessor.write().unwrap() = None;
+    }
+
+    /// Sets a validator that is checked before any handler is called.
+    ///
+    /// If the validator rejects the arguments, no handlers are called; [`Event::try_invoke`]
+    /// fails with [`EventInvocationError::InvalidArgs`], while [`Event::invoke`] does nothing.
+    /// The validator sees the arguments after preprocessing.
+    pub fn set_validator<V>(&self, validator: V)
+    where
+        V: Fn(&TEventArgs) -> bool + Send + Sync + 'static,
+    {
+        *self
+            .handlers
+            .validator
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(validator));
+    }
+
+    /// Suppresses invocations with arguments equal to ones seen within the last `window`.
+    ///
+    /// Suppressed invocations call no handlers, as if rejected by the validator
+    /// (see [`Event::set_validator`]). Arguments outside the window are forgotten
+    /// on the next invocation.
+    pub fn set_dedup_window(&self, window: Duration)
+    where
+        TEventArgs: Eq + Hash + Clone + Send + 'static,
+    {
+        let seen: Mutex<HashMap<TEventArgs, Instant>> = Mutex::new(HashMap::new());
+        let dedup = move |args: &TEventArgs| {
+            let now = Instant::now();
+            let mut seen = seen.lock().unwrap();
+            seen.retain(|_, at| now.duration_since(*at) < window);
+            if seen.contains_key(args) {
+                return false;
+            }
+            seen.insert(args.clone(), now);
+            true
+        };
+        *self.handlers.dedup.write().unwrap() = Some(Arc::new(dedup));
+    }
+
+    /// Invokes the event, applying the preprocessor first.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The number of handlers called.
+    ///
+    /// ## Errors
+    /// Returns the error of the preprocessor without calling any handler, or
+    /// [`PreprocessError::OtherType`] if it is not of type `E`.
+    pub fn invoke_try<E>(&self, args: TEventArgs) -> Result<usize, PreprocessError<E>>
+    where
+        TEventArgs: Clone,
+        E: 'static,
+    {
+        match self.handlers.preprocess(args) {
+            Ok(args) => Ok(self.handlers.invoke_unprocessed(args)),
+            Err(error) => match error.downcast::<E>() {
+                Ok(error) => Err(PreprocessError::Rejected(*error)),
+                Err(_) => Err(PreprocessError::OtherType),
+            },
+        }
+    }
+
+    /// Invokes the event, passing the accumulator to the handlers registered via
+    /// [`Event::add_fn_acc`].
+    ///
+    /// The accumulator is locked for the duration of each such handler call.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    /// * `acc` - The accumulator to gather the results in.
+    pub fn invoke_with_accumulator<Acc>(&self, args: TEventArgs, acc: &Mutex<Acc>)
+    where
+        TEventArgs: Clone,
+        Acc: 'static,
+    {
+        let _scope = accumulator::AccumulatorScope::enter(self.handlers.address(), acc);
+        self.handlers.invoke(args);
+    }
+
+    /// Invokes the event, collecting the errors of the handlers registered via
+    /// [`Event::add_try_fn`] with error type `E`.
+    ///
+    /// All handlers are called, regardless of failures.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Errors
+    /// Fails with the errors of all failed handlers if any handler failed.
+    pub fn invoke_try_all<E>(&self, args: TEventArgs) -> Result<(), HandlerErrors<E>>
+    where
+        TEventArgs: Clone,
+        E: 'static,
+    {
+        let errors = Mutex::new(Vec::new());
+        {
+            let _scope = accumulator::AccumulatorScope::enter(self.handlers.address(), &errors);
+            self.handlers.invoke(args);
+        }
+        let errors = errors.into_inner().unwrap();
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(HandlerErrors { errors })
+        }
+    }
+
+    /// Invokes the event until a handler registered via [`Event::add_try_fn`]
+    /// with error type `E` fails; the remaining handlers are not called.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Errors
+    /// Fails with the error of the first failed handler.
+    pub fn try_invoke_first_err<E>(&self, args: TEventArgs) -> Result<(), E>
+    where
+        TEventArgs: Clone,
+        E: 'static,
+    {
+        let errors: Mutex<Vec<(RegistrationId, E)>> = Mutex::new(Vec::new());
+        self.invoke_accumulating_until(args, &errors, |errors| !errors.is_empty());
+        match errors.into_inner().unwrap().into_iter().next() {
+            Some((_, error)) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Invokes the event until a handler registered via [`Event::add_fn_flow`]
+    /// returns [`ControlFlow::Break`]; the remaining handlers are not called.
+    ///
+    /// Combined with priorities, this allows handlers to handle an event before
+    /// it reaches handlers of lower priority.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// Whether a handler stopped the invocation.
+    pub fn invoke_until(&self, args: TEventArgs) -> bool
+    where
+        TEventArgs: Clone,
+    {
+        let stopped = Mutex::new(Stopped(false));
+        self.invoke_accumulating_until(args, &stopped, |stopped| stopped.0);
+        let stopped = stopped.into_inner().unwrap();
+        stopped.0
+    }
+
+    /// Calls the handlers in order, passing the accumulator to them like
+    /// [`Event::invoke_with_accumulator`], until `is_done` holds for the accumulator.
+    fn invoke_accumulating_until<Acc>(
+        &self,
+        args: TEventArgs,
+        acc: &Mutex<Acc>,
+        is_done: impl Fn(&Acc) -> bool,
+    ) where
+        TEventArgs: Clone,
+        Acc: 'static,
+    {
+        let (_, unregister_list) = {
+            let _scope = accumulator::AccumulatorScope::enter(self.handlers.address(), acc);
+            let handlers = self.handlers.current();
+            let remaining = self
+                .handlers
+                .ordered(&handlers)
+                .take_while(|_| !is_done(&acc.lock().unwrap()));
+            self.handlers.dispatch(remaining, args)
+        };
+
+        self.handlers.unregister_all(unregister_list);
+    }
+
+    /// Invokes the event as part of the specified trace.
+    ///
+    /// Handlers registered via [`Event::add_fn_traced`] receive the trace identifier,
+    /// including handlers of events this invocation is forwarded to.
+    ///
+    /// ## Arguments
+    /// * `trace_id` - The identifier of the trace.
+    /// * `args` - The event arguments.
+    pub fn invoke_traced_id(&self, trace_id: u128, args: TEventArgs)
+    where
+        TEventArgs: Clone,
+    {
+        let _scope = trace::TraceScope::enter(trace_id);
+        self.handlers.invoke(args);
+    }
+
+    /// Invokes the event, awaiting the handlers concurrently.
+    ///
+    /// Synchronous handlers are called as well; the returned future completes
+    /// once all handlers have completed.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    #[cfg(feature = "futures")]
+    pub async fn invoke_async(&self, args: TEventArgs)
+    where
+        TEventArgs: Clone,
+    {
+        let handlers = self.handlers.snapshot();
+        let calls = handlers
+            .iter()
+            .map(|(_, entry)| entry.handler.call_async(args.clone()));
+        futures::future::join_all(calls).await;
+    }
+
+    /// Invokes the event, calling the handlers sequentially in ascending order
+    /// of their priority and awaiting asynchronous handlers.
+    ///
+    /// Handlers registered without a priority have priority `0`; handlers of equal
+    /// priority are called in the order they were registered.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    #[cfg(feature = "futures")]
+    pub async fn invoke_async_ordered(&self, args: TEventArgs)
+    where
+        TEventArgs: Clone,
+    {
+        let mut handlers = self.handlers.snapshot();
+        handlers.sort_by_key(|(_, entry)| (entry.priority, entry.sequence));
+        for (_, entry) in handlers {
+            entry.handler.call_async(args.clone()).await;
+        }
+    }
+
+    /// Invokes the event like [`Event::invoke_async_ordered`], stopping once the token is cancelled.
+    ///
+    /// Handlers registered via [`Event::add_async_cancellable`] are aborted mid-flight
+    /// when the token fires; other handlers run to completion. Handlers that have not
+    /// started when the token fires are not called.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    /// * `token` - Cancels the invocation.
+    ///
+    /// ## Returns
+    /// The number of handlers that completed.
+    #[cfg(feature = "futures")]
+    pub async fn invoke_async_cancellable(
+        &self,
+        args: TEventArgs,
+        token: CancellationToken,
+    ) -> usize
+    where
+        TEventArgs: Clone,
+    {
+        let mut handlers = self.handlers.snapshot();
+        handlers.sort_by_key(|(_, entry)| (entry.priority, entry.sequence));
+
+        let mut completed = 0;
+        for (_, entry) in handlers {
+            if token.is_cancelled() {
+                break;
+            }
+
+            let call = entry.handler.call_async(args.clone());
+            let called = if entry.cancellable {
+                futures::pin_mut!(call);
+                match futures::future::select(call, token.cancelled()).await {
+                    futures::future::Either::Left((called, _)) => called,
+                    futures::future::Either::Right(_) => break,
+                }
+            } else {
+                call.await
+            };
+            if called {
+                completed += 1;
+            }
+        }
+        completed
+    }
+
+    /// Invokes the event like [`Event::invoke`], catching panics of individual handlers.
+    ///
+    /// The preprocessor, the validator and the handler order apply as they do for
+    /// [`Event::invoke`]. A panicking handler does not prevent the remaining handlers
+    /// from being called. Handlers are called without holding the lock, so panics
+    /// never poison the event.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The handlers that panicked, in the order they were called.
+    pub fn invoke_panic_safe(&self, args: TEventArgs) -> Vec<HandlerFailure>
+    where
+        TEventArgs: Clone,
+    {
+        let mut failures = Vec::new();
+        let Ok(args) = self.handlers.preprocess(args) else {
+            return failures;
+        };
+
+        self.handlers.invoke_via(args, |key, entry, args| {
+            let call = AssertUnwindSafe(|| self.handlers.call(entry, args));
+            panic::catch_unwind(call).unwrap_or_else(|payload| {
+                failures.push(HandlerFailure {
+                    id: RegistrationId(key),
+                    message: panic_message(payload.as_ref()),
+                });
+                true
+            })
+        });
+        failures
+    }
+
+    /// Invokes the event like [`Event::invoke_panic_safe`], reporting only the
+    /// registrations of the handlers that panicked.
+    ///
+    /// In programs built with `panic = "abort"`, panics cannot be caught: the first
+    /// panicking handler terminates the process. Use [`Event::invoke_safe_checked`]
+    /// to detect that case.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The registrations of the handlers that panicked, in the order they were called.
+    pub fn invoke_catching(&self, args: TEventArgs) -> Vec<RegistrationId>
+    where
+        TEventArgs: Clone,
+    {
+        self.invoke_panic_safe(args)
+            .into_iter()
+            .map(|failure| failure.id)
+            .collect()
+    }
+
+    /// Invokes the event like [`Event::invoke_catching`], but reports whether
+    /// panics could actually be isolated, along with the panic messages.
+    ///
+    /// In programs built with `panic = "abort"`, a panicking handler terminates the process
+    /// regardless of any attempt to catch it. In that case the handlers are invoked without
+    /// isolation, as by [`Event::invoke`], and the missing guarantee is reported as an error
+    /// once they completed.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The handlers that panicked.
+    ///
+    /// ## Errors
+    /// Returns [`PanicIsolationUnavailable`] after invoking the handlers if panics abort the process.
+    pub fn invoke_safe_checked(
+        &self,
+        args: TEventArgs,
+    ) -> Result<Vec<HandlerFailure>, PanicIsolationUnavailable>
+    where
+        TEventArgs: Clone,
+    {
+        if Self::panic_isolation_available() {
+            Ok(self.invoke_panic_safe(args))
+        } else {
+            self.invoke(args);
+            Err(PanicIsolationUnavailable)
+        }
+    }
+
+    /// Determines whether panics of handlers can be caught, i.e. whether
+    /// the program was built with unwinding panics.
+    pub fn panic_isolation_available() -> bool {
+        cfg!(panic = "unwind")
+    }
+
+    /// Invokes the event like [`Event::invoke`], reporting how the invocation was executed.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    pub fn invoke_with_stats(&self, args: TEventArgs) -> InvokeStats
+    where
+        TEventArgs: Clone,
+    {
+        match self.handlers.preprocess(args) {
+            Ok(args) => self
+                .handlers
+                .invoke_via(args, |_, entry, args| self.handlers.call(entry, args)),
+            Err(_) => InvokeStats {
+                short_circuited: true,
+                ..InvokeStats::default()
+            },
+        }
+    }
+
+    /// Invokes the event with lazily constructed arguments.
+    ///
+    /// The arguments are only constructed if handlers are registered, which avoids
+    /// potentially expensive or fallible work when nobody is listening.
+    ///
+    /// ## Arguments
+    /// * `build` - Constructs the event arguments.
+    ///
+    /// ## Returns
+    /// The number of handlers called.
+    ///
+    /// ## Errors
+    /// Returns the error of `build` without calling any handler.
+    pub fn invoke_try_build<F, E>(&self, build: F) -> Result<usize, E>
+    where
+        F: FnOnce() -> Result<TEventArgs, E>,
+        TEventArgs: Clone,
+    {
+        if self.is_empty() {
+            return Ok(0);
+        }
+
+        let args = build()?;
+        Ok(self.handlers.invoke(args))
+    }
+
+    /// Invokes the event, reporting invocations that did not reach any handler.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Errors
+    /// * [`EventInvocationError::BelowMinimum`] if fewer handlers than configured
+    ///   via [`Event::set_min_handlers`] are registered.
+    /// * [`EventInvocationError::NoHandlers`] if no handlers are registered.
+    pub fn try_invoke(&self, args: TEventArgs) -> Result<(), EventInvocationError>
+    where
+        TEventArgs: Clone,
+    {
+        self.handlers.try_invoke(args).map(|_| ())
+    }
+
+    /// Invokes the event like [`Event::invoke`], measuring the time each handler takes.
+    ///
+    /// With the `log` feature enabled, handlers exceeding the threshold configured via
+    /// [`Event::set_slow_handler_threshold`] are reported as warnings. For events with
+    /// a queue or scheduler, only handing the call over is measured.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The time taken by each called handler, in the order they were called.
+    pub fn invoke_timed(&self, args: TEventArgs) -> Vec<(RegistrationId, Duration)>
+    where
+        TEventArgs: Clone,
+    {
+        let mut timings = Vec::new();
+        let Ok(args) = self.handlers.preprocess(args) else {
+            return timings;
+        };
+
+        self.handlers.invoke_via(args, |key, entry, args| {
+            let start = Instant::now();
+            let called = self.handlers.call(entry, args);
+            let elapsed = start.elapsed();
+
+            #[cfg(feature = "log")]
+            self.handlers.warn_if_slow(key, elapsed);
+
+            if called {
+                timings.push((RegistrationId(key), elapsed));
+            }
+            called
+        });
+        timings
+    }
+
+    /// Invokes the event like [`Event::invoke`] until the total time budget is exhausted.
+    ///
+    /// A handler is only started if the time elapsed since the start of the invocation
+    /// is still within `total`; running handlers are not interrupted. One-shot handlers
+    /// that were not reached remain registered.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    /// * `total` - The time budget of the whole invocation.
+    ///
+    /// ## Returns
+    /// The number of handlers called.
+    pub fn invoke_total_deadline(&self, args: TEventArgs, total: Duration) -> usize
+    where
+        TEventArgs: Clone,
+    {
+        let start = Instant::now();
+        let Ok(args) = self.handlers.preprocess(args) else {
+            return 0;
+        };
+
+        let within_budget = |_: &Registration<TEventArgs>| start.elapsed() <= total;
+        self.handlers
+            .invoke_selected(args, within_budget, |_, entry, args| {
+                self.handlers.call(entry, args)
+            })
+            .fired
+    }
+
+    /// Sets the duration after which [`Event::invoke_timed`] logs a warning about a handler.
+    ///
+    /// Pass `None` to disable the warnings.
+    #[cfg(feature = "log")]
+    pub fn set_slow_handler_threshold(&self, threshold: Option<Duration>) {
+        *self.handlers.slow_handler_threshold.lock().unwrap() = threshold;
+    }
+
+    /// Invokes the event like [`Event::invoke`], but only calls the handlers of the
+    /// specified group.
+    ///
+    /// One-shot handlers are only consumed if they belong to the invoked group;
+    /// one-shot handlers of other groups remain registered.
+    ///
+    /// ## Arguments
+    /// * `tag` - The group to invoke.
+    /// * `args` - The event arguments.
+    pub fn invoke_tagged(&self, tag: u32, args: TEventArgs)
+    where
+        TEventArgs: Clone,
+    {
+        let Ok(args) = self.handlers.preprocess(args) else {
+            return;
+        };
+
+        let in_group = |entry: &Registration<TEventArgs>| entry.tag == Some(tag);
+        self.handlers
+            .invoke_selected(args, in_group, |_, entry, args| {
+                self.handlers.call(entry, args)
+            });
+    }
+
+    /// Invokes the event like [`Event::invoke`], but only calls the handlers registered
+    /// for the topic of the arguments, see [`Event::add_fn_for_topic`].
+    ///
+    /// The topic is determined before the preprocessor is applied.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    /// * `topic_of` - Determines the topic of the arguments.
+    pub fn invoke_topic<F>(&self, args: TEventArgs, topic_of: F)
+    where
+        TEventArgs: Clone,
+        F: Fn(&TEventArgs) -> String,
+    {
+        let topic = topic_of(&args);
+        let Ok(args) = self.handlers.preprocess(args) else {
+            return;
+        };
+
+        let subscribed = |entry: &Registration<TEventArgs>| entry.topic.as_ref() == Some(&topic);
+        self.handlers
+            .invoke_selected(args, subscribed, |_, entry, args| {
+                self.handlers.call(entry, args)
+            });
+    }
+
+    /// Invokes the event like [`Event::invoke`], but only calls the first `n` live
+    /// handlers in handler order.
+    ///
+    /// Handlers that would not be called, such as disabled flagged handlers or
+    /// consumed one-shot handlers, are skipped without counting towards `n`.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    /// * `n` - The maximum number of handlers to call.
+    ///
+    /// ## Returns
+    /// The number of handlers called.
+    pub fn invoke_first_n(&self, args: TEventArgs, n: usize) -> usize
+    where
+        TEventArgs: Clone,
+    {
+        let Ok(args) = self.handlers.preprocess(args) else {
+            return 0;
+        };
+
+        // Stops once `n` handlers were called, skipping those not called by this invocation.
+        let fired = Cell::new(0);
+        let first = |_: &Registration<TEventArgs>| fired.get() < n;
+        self.handlers
+            .invoke_selected(args, first, |_, entry, args| {
+                let called = self.handlers.call(entry, args);
+                fired.set(fired.get() + usize::from(called));
+                called
+            })
+            .fired
+    }
+
+    /// Invokes the event like [`Event::invoke`], but only calls the specified handlers,
+    /// in exactly the specified order.
+    ///
+    /// Registrations that do not exist (anymore) are skipped, handlers not listed are not called.
+    /// This allows tests to drive arbitrary handler permutations.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    /// * `order` - The registrations to invoke.
+    pub fn invoke_with_order(&self, args: TEventArgs, order: &[RegistrationId])
+    where
+        TEventArgs: Clone,
+    {
+        let Ok(args) = self.handlers.preprocess(args) else {
+            return;
+        };
+
+        self.handlers.invoke_checked(args, |handlers, args| {
+            let listed = order.iter().filter_map(|id| handlers.get_key_value(&id.0));
+            self.handlers.dispatch_with_stats(listed, args)
+        });
+    }
+
+    /// Invokes the event like [`Event::invoke`], but only calls the handler with the
+    /// specified registration.
+    ///
+    /// This allows request/reply style routing, where each invocation targets
+    /// a single subscriber rather than the whole set.
+    ///
+    /// ## Arguments
+    /// * `id` - The registration to invoke.
+    /// * `args` - The event arguments.
+    ///
+    /// ## Errors
+    /// Returns [`EventInvocationError::NoSuchHandler`] if no handler with that
+    /// registration exists (anymore).
+    pub fn invoke_to(
+        &self,
+        id: RegistrationId,
+        args: TEventArgs,
+    ) -> Result<(), EventInvocationError>
+    where
+        TEventArgs: Clone,
+    {
+        if !self.is_registered(id) {
+            return Err(EventInvocationError::NoSuchHandler);
+        }
+        let Ok(args) = self.handlers.preprocess(args) else {
+            return Ok(());
+        };
+
+        self.handlers.invoke_checked(args, |handlers, args| {
+            let target = handlers.get_key_value(&id.0);
+            self.handlers.dispatch_with_stats(target, args)
+        });
+        Ok(())
+    }
+
+    /// Executes up to `max` handler calls queued by invocations of an event
+    /// created by [`Event::queued`].
+    ///
+    /// ## Arguments
+    /// * `max` - The maximum number of calls to execute.
+    ///
+    /// ## Returns
+    /// The number of calls executed.
+    pub fn pump(&self, max: usize) -> usize {
+        let Some(queue) = &self.handlers.queue else {
+            return 0;
+        };
+
+        let mut executed = 0;
+        while executed < max {
+            // The lock is released before the call, so handlers may invoke the event.
+            let Some((entry, args)) = queue.lock().unwrap().pop() else {
+                break;
+            };
+            let _depth = self.handlers.enter();
+            entry.handler.call(args);
+            executed += 1;
+        }
+        executed
+    }
+
+    /// Returns the number of handler calls waiting for [`Event::pump`].
+    pub fn pending(&self) -> usize {
+        self.handlers
+            .queue
+            .as_ref()
+            .map_or(0, |queue| queue.lock().unwrap().len())
+    }
+
+    /// Moves the arguments into the first handler without cloning them;
+    /// any further handlers are not called.
+    ///
+    /// Returns whether a handler was called.
+    pub(crate) fn invoke_moved(&self, args: TEventArgs) -> bool
+    where
+        TEventArgs: Clone,
+    {
+        if !self.handlers.is_enabled() {
+            return false;
+        }
+        let sequence = self.handlers.next_sequence();
+        let first = {
+            let handlers = self.handlers.read_map();
+            handlers
+                .iter()
+                .find(|(_, entry)| self.handlers.is_active(entry, sequence))
+                .map(|(key, entry)| (*key, entry.clone()))
+        };
+        let Some((key, entry)) = first else {
+            return false;
+        };
+
+        let called = {
+            let _depth = self.handlers.enter();
+            entry.handler.call(args)
+        };
+        if entry.handler.is_once() {
+            self.handlers.unregister_all(vec![key]);
+        }
+        called
+    }
+
+    /// Invokes all handlers such that every handler runs after the
+    /// registration it was declared to depend on via [`Event::add_fn_after`].
+    ///
+    /// Handlers without constraints between them are called in the usual order.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Errors
+    /// Returns [`EventInvocationError::DependencyCycle`] without calling any handler
+    /// if the dependencies form a cycle.
+    pub fn invoke_topological(&self, args: TEventArgs) -> Result<(), EventInvocationError>
+    where
+        TEventArgs: Clone,
+    {
+        let (_, unregister_list) = {
+            let handlers = self.handlers.current();
+
+            let mut pending = HashMap::new();
+            let mut dependents: HashMap<HandleKey, Vec<HandleKey>> = HashMap::new();
+            let mut ready = VecDeque::new();
+            for (key, entry) in handlers.iter() {
+                match entry.after().filter(|after| handlers.contains_key(after)) {
+                    Some(after) => {
+                        pending.insert(*key, 1);
+                        dependents.entry(after).or_default().push(*key);
+                    }
+                    None => ready.push_back(*key),
+                }
+            }
+
+            let mut sorted = Vec::with_capacity(handlers.len());
+            while let Some(key) = ready.pop_front() {
+                for dependent in dependents.remove(&key).unwrap_or_default() {
+                    let count = pending.get_mut(&dependent).unwrap();
+                    *count -= 1;
+                    if *count == 0 {
+                        ready.push_back(dependent);
+                    }
+                }
+                sorted.push(key);
+            }
+
+            if sorted.len() != handlers.len() {
+                return Err(EventInvocationError::DependencyCycle);
+            }
+
+            let ordered = sorted.iter().filter_map(|key| handlers.get_key_value(key));
+            self.handlers.dispatch(ordered, args)
+        };
+
+        self.handlers.unregister_all(unregister_list);
+        Ok(())
+    }
+
+    /// Invokes all handlers in parallel, each on its own thread, and waits
+    /// at most `per_handler` for them to complete.
+    ///
+    /// Handlers that did not complete in time keep running on their detached threads;
+    /// their registrations are returned. Handlers that panicked never report completion
+    /// and are returned as well.
+    ///
+    /// If no thread can be spawned for a handler, it is called on the current thread
+    /// instead, without being subject to the timeout.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    /// * `per_handler` - The time each handler is given to complete.
+    pub fn invoke_par_timeout(&self, args: TEventArgs, per_handler: Duration) -> Vec<RegistrationId>
+    where
+        TEventArgs: Clone + Send + Sync + 'static,
+    {
+        let handlers = self.handlers.snapshot();
+        let deadline = Instant::now() + per_handler;
+
+        let (sender, receiver) = mpsc::channel();
+        let mut pending = BTreeSet::new();
+        for (key, entry) in handlers {
+            pending.insert(key);
+            let spawned = self.handler_thread().spawn({
+                let (entry, args, sender) = (entry.clone(), args.clone(), sender.clone());
+                move || {
+                    entry.handler.call(args);
+                    sender.send(key).ok();
+                }
+            });
+
+            if spawned.is_err() {
+                let call = AssertUnwindSafe(|| entry.handler.call(args.clone()));
+                if panic::catch_unwind(call).is_ok() {
+                    pending.remove(&key);
+                }
+            }
+        }
+        drop(sender);
+
+        while !pending.is_empty() {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match receiver.recv_timeout(remaining) {
+                Ok(key) => {
+                    pending.remove(&key);
+                }
+                Err(_) => break,
+            }
+        }
+
+        pending.into_iter().map(RegistrationId).collect()
+    }
+
+    /// Invokes all handlers on `workers` threads. The handlers are spread over
+    /// one queue per worker; a worker that drained its own queue steals handlers
+    /// from the back of the others' queues while they are still busy.
+    ///
+    /// Returns once all handlers completed. A panicking handler does not prevent
+    /// the remaining handlers from being called. If no thread could be spawned,
+    /// the handlers are called on the current thread.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    /// * `workers` - The number of threads to use; at least one thread is used.
+    ///
+    /// ## Returns
+    /// The number of handlers each worker called, and the handlers that panicked.
+    pub fn invoke_work_stealing(&self, args: TEventArgs, workers: usize) -> WorkStealingStats
+    where
+        TEventArgs: Clone + Send + Sync,
+    {
+        let workers = workers.max(1);
+        let mut queues: Vec<_> = (0..workers).map(|_| VecDeque::new()).collect();
+        for (index, handler) in self.handlers.snapshot().into_iter().enumerate() {
+            queues[index % workers].push_back(handler);
+        }
+        let queues: Vec<_> = queues.into_iter().map(Mutex::new).collect();
+        let failures = Mutex::new(Vec::new());
+
+        let work = |worker: usize| {
+            let mut called = 0;
+            while let Some((key, entry)) = steal_next(&queues, worker) {
+                let call = AssertUnwindSafe(|| entry.handler.call(args.clone()));
+                match panic::catch_unwind(call) {
+                    Ok(true) => called += 1,
+                    Ok(false) => {}
+                    Err(payload) => failures
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push(HandlerFailure {
+                            id: RegistrationId(key),
+                            message: panic_message(payload.as_ref()),
+                        }),
+                }
+            }
+            called
+        };
+
+        let called = thread::scope(|scope| {
+            let spawned: Vec<_> = (0..workers)
+                .filter_map(|worker| {
+                    let work = &work;
+                    let thread = self.handler_thread();
+                    thread.spawn_scoped(scope, move || work(worker)).ok()
+                })
+                .collect();
+
+            if spawned.is_empty() {
+                return vec![work(0)];
+            }
+            spawned
+                .into_iter()
+                .map(|worker| worker.join().unwrap_or_default())
+                .collect()
+        });
+
+        WorkStealingStats {
+            called,
+            failures: failures
+                .into_inner()
+                .unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+}
+
+/// Takes the next handler from the front of the worker's own queue, or steals one
+/// from the back of another worker's queue once its own is drained.
+fn steal_next<T>(queues: &[Mutex<VecDeque<T>>], worker: usize) -> Option<T> {
+    let take = |queue: &Mutex<VecDeque<T>>| {
+        queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front()
+    };
+    let steal = |queue: &Mutex<VecDeque<T>>| {
+        queue
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_back()
+    };
+
+    take(&queues[worker]).or_else(|| {
+        (1..queues.len()).find_map(|offset| steal(&queues[(worker + offset) % queues.len()]))
+    })
+}
+
+/// Extracts the message of a panic payload.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("Box<dyn Any>")
+    }
+}
+
+impl<A, B> Event<(A, B)>
+where
+    A: Clone,
+    B: Clone,
+{
+    /// Fixes the first element of the event arguments ahead of time.
+    ///
+    /// Calling the returned closure with `b` invokes the event with `(a, b)`.
+    ///
+    /// ## Arguments
+    /// * `a` - The first element of the event arguments.
+    pub fn bind_first(&self, a: A) -> impl Fn(B) + '_ {
+        move |b| {
+            self.invoke((a.clone(), b));
+        }
+    }
+}
+
+impl Default for Event {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Clones share the registered handlers, so each clone may register handlers
+/// and invoke them. The handlers are dropped once the last clone is dropped.
+impl<TEventArgs> Clone for Event<TEventArgs> {
+    fn clone(&self) -> Self {
+        Self {
+            handlers: self.handlers.clone(),
+        }
+    }
+}
+
+impl<TEventArgs> Debug for Event<TEventArgs> {
+    /// Formats the number of handlers, broken down by their kind; kinds without
+    /// any handler are omitted.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let handlers = self.handlers.current();
+        let mut debug = f.debug_struct("Event");
+        debug.field("handlers", &handlers.len());
+        for (kind, name) in [
+            (HandlerKind::Fn, "fn"),
+            (HandlerKind::FnOnce, "fn_once"),
+            (HandlerKind::FnRef, "fn_ref"),
+            (HandlerKind::FnMut, "fn_mut"),
+            (HandlerKind::Function, "ptr"),
+            (HandlerKind::Async, "async"),
+        ] {
+            let count = handlers
+                .iter()
+                .filter(|(_, entry)| entry.handler.kind() == kind)
+                .count();
+            if count > 0 {
+                debug.field(name, &count);
+            }
+        }
+        debug.finish()
+    }
+}
+
+impl<TEventArgs> MapLocked<TEventArgs> {
+    fn new(inner: MapInner<TEventArgs>) -> Self {
+        Self {
+            map: RwLock::new(Arc::new(inner)),
+            frozen: AtomicBool::new(false),
+            enabled: AtomicBool::new(true),
+            min_handlers: AtomicUsize::new(0),
+            order_policy: Mutex::new(OrderPolicy::Sequential),
+            order_comparator: Mutex::new(None),
+            reentrancy: Mutex::new(Reentrancy::Allow),
+            reentrant: Mutex::new(VecDeque::new()),
+            rotation: AtomicUsize::new(0),
+            invocations: AtomicU64::new(0),
+            registrations: AtomicU64::new(0),
+            max_handlers: AtomicUsize::new(usize::MAX),
+            handler_stack_size: AtomicUsize::new(0),
+            on_subscribe: RwLock::new(None),
+            on_unsubscribe: RwLock::new(None),
+            on_unhandled: RwLock::new(None),
+            on_overflow: Mutex::new(None),
+            preprocessor: RwLock::new(None),
+            validator: RwLock::new(None),
+            dedup: RwLock::new(None),
+            subscriptions: Mutex::new(HashMap::new()),
+            in_flight: Mutex::new(HashSet::new()),
+            flags: None,
+            queue: None,
+            scheduler: None,
+            audit: None,
+            #[cfg(feature = "allocator")]
+            allocator: None,
+            #[cfg(feature = "log")]
+            slow_handler_threshold: Mutex::new(None),
+        }
+    }
+
+    /// Tracks an invocation of this event on the current thread.
+    fn enter(&self) -> depth::DepthScope {
+        depth::DepthScope::enter(self.address())
+    }
+
+    /// Returns the address identifying this event.
+    fn address(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    /// Returns the registered handlers, without holding the lock.
+    fn current(&self) -> Arc<MapInner<TEventArgs>> {
+        self.read_map().clone()
+    }
+
+    /// Locks the handler map for reading.
+    ///
+    /// Handlers are called without holding the lock, so it can only be poisoned by a
+    /// panic within the crate; the map is never left inconsistent, so poisoning is ignored.
+    fn read_map(&self) -> RwLockReadGuard<'_, Arc<MapInner<TEventArgs>>> {
+        self.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Locks the handler map for writing, ignoring poisoning like [`MapLocked::read_map`].
+    fn write_map(&self) -> RwLockWriteGuard<'_, Arc<MapInner<TEventArgs>>> {
+        self.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Determines whether invocations call the handlers.
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    /// Blocks or unblocks the handler, returning whether it is registered.
+    fn set_blocked(&self, key: &HandleKey, blocked: bool) -> bool {
+        let handlers = self.read_map();
+        let Some((_, entry)) = handlers.get_key_value(key) else {
+            return false;
+        };
+        entry.blocked.store(blocked, Ordering::Release);
+        true
+    }
+
+    /// Sets the registration the handler must run after, returning whether it is registered.
+    fn set_after(&self, key: &HandleKey, after: HandleKey) -> bool {
+        let handlers = self.read_map();
+        let Some((_, entry)) = handlers.get_key_value(key) else {
+            return false;
+        };
+        *entry.after.lock().unwrap_or_else(PoisonError::into_inner) = Some(after);
+        true
+    }
+
+    /// Removes the handler, returning whether it was registered.
+    fn remove(&self, key: &HandleKey) -> bool {
+        let mut handlers = self.write_map();
+        // Avoids copying a map that is in use if there is nothing to remove.
+        if !handlers.contains_key(key) {
+            return false;
+        }
+        Arc::make_mut(&mut handlers).remove(key).is_some()
+    }
+
+    /// Calls the lifecycle callback, if any, for each of the keys.
+    ///
+    /// Must be called without holding the handler lock, so the callback may use the event.
+    fn notify(
+        &self,
+        callback: &RwLock<Option<Arc<LifecycleCallback>>>,
+        keys: impl IntoIterator<Item = HandleKey>,
+    ) {
+        let callback = callback.read().unwrap().clone();
+        if let Some(callback) = callback {
+            keys.into_iter()
+                .for_each(|key| callback(RegistrationId(key)));
+        }
+    }
+
+    /// Determines whether the validator and the deduplication window, if any,
+    /// accept the arguments.
+    fn is_valid(&self, args: &TEventArgs) -> bool {
+        let validator = self
+            .validator
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        if !validator.map_or(true, |validator| validator(args)) {
+            return false;
+        }
+        let dedup = self.dedup.read().unwrap().clone();
+        dedup.map_or(true, |dedup| dedup(args))
+    }
+
+    /// Iterates the handlers in the order of the comparator or, if none is set,
+    /// in ascending order of their priority and then their registration. Handlers the
+    /// comparator orders equally, or all handlers if no priorities were assigned, are
+    /// iterated in the order given by the order policy.
+    fn ordered<'a>(&self, handlers: &'a MapInner<TEventArgs>) -> OrderedIter<'a, TEventArgs> {
+        let start = match *self
+            .order_policy
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            OrderPolicy::Sequential => 0,
+            OrderPolicy::RotatingStart if handlers.is_empty() => 0,
+            OrderPolicy::RotatingStart => {
+                self.rotation.fetch_add(1, Ordering::Relaxed) % handlers.len()
+            }
+        };
+        let rotated = handlers
+            .iter()
+            .skip(start)
+            .chain(handlers.iter().take(start));
+
+        if let Some(comparator) = &*self
+            .order_comparator
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+        {
+            let mut sorted: Vec<_> = rotated.collect();
+            sorted.sort_by(|(a_key, a), (b_key, b)| comparator(&a.meta(**a_key), &b.meta(**b_key)));
+            return OrderedIter::Sorted(sorted.into_iter());
+        }
+
+        // Only sort, and allocate, if priorities were assigned.
+        if handlers.iter().all(|(_, entry)| entry.priority == 0) {
+            return OrderedIter::Rotated(rotated);
+        }
+        let mut sorted: Vec<_> = rotated.collect();
+        sorted.sort_by_key(|(_, entry)| (entry.priority, entry.sequence));
+        OrderedIter::Sorted(sorted.into_iter())
+    }
+
+    /// Determines whether fewer handlers than required are registered.
+    fn is_below_minimum(&self, handlers: &MapInner<TEventArgs>) -> bool {
+        handlers.len() < self.min_handlers.load(Ordering::Acquire)
+    }
+
+    /// Starts an invocation, returning its sequence number for [`MapLocked::is_active`].
+    fn next_sequence(&self) -> u64 {
+        self.invocations
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_add(1)
+    }
+
+    /// Determines whether the invocation with the specified sequence number calls the
+    /// handler, i.e. it is not blocked, its feature flag, if any, is enabled, and it is
+    /// due according to [`Event::add_fn_every`].
+    fn is_active(&self, entry: &Registration<TEventArgs>, sequence: u64) -> bool {
+        !entry.blocked.load(Ordering::Acquire)
+            && self.is_flag_enabled(entry)
+            && entry.every.map_or(true, |n| sequence % n as u64 == 0)
+    }
+
+    /// Determines whether the feature flag of the registration, if any, is enabled.
+    fn is_flag_enabled(&self, entry: &Registration<TEventArgs>) -> bool {
+        let Some(flag) = &entry.flag else {
+            return true;
+        };
+        self.flags.as_ref().map_or(false, |flags| {
+            flags.read().unwrap().get(flag).copied().unwrap_or(false)
+        })
+    }
+}
+
+impl<TEventArgs> MapLocked<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    fn invoke(&self, args: TEventArgs) -> usize {
+        match self.preprocess(args) {
+            Ok(args) => self.invoke_unprocessed(args),
+            Err(_) => 0,
+        }
+    }
+
+    /// Applies the preprocessor, if any, to the arguments.
+    fn preprocess(&self, args: TEventArgs) -> Result<TEventArgs, Box<dyn Any + Send>> {
+        let preprocessor = self.preprocessor.read().unwrap().clone();
+        match preprocessor {
+            Some(preprocessor) => preprocessor(args),
+            None => Ok(args),
+        }
+    }
+
+    /// Invokes the event without applying the preprocessor.
+    fn invoke_unprocessed(&self, args: TEventArgs) -> usize {
+        self.invoke_via(args, |_, entry, args| self.call(entry, args))
+            .fired
+    }
+
+    /// Invokes the event without applying the preprocessor, calling each handler through `call`.
+    fn invoke_via<F>(&self, args: TEventArgs, call: F) -> InvokeStats
+    where
+        F: FnMut(HandleKey, &Arc<Registration<TEventArgs>>, TEventArgs) -> bool,
+    {
+        self.invoke_selected(args, |_| true, call)
+    }
+
+    /// Invokes the event like [`MapLocked::invoke_via`], skipping the handlers `select` rejects.
+    ///
+    /// `select` is checked right before each handler in turn, so it may depend on
+    /// the handlers called so far.
+    fn invoke_selected<S, F>(&self, args: TEventArgs, mut select: S, call: F) -> InvokeStats
+    where
+        S: FnMut(&Registration<TEventArgs>) -> bool,
+        F: FnMut(HandleKey, &Arc<Registration<TEventArgs>>, TEventArgs) -> bool,
+    {
+        self.invoke_checked(args, |handlers, args| {
+            let selected = self.ordered(handlers).filter(|(_, entry)| select(entry));
+            self.dispatch_via(selected, args, call)
+        })
+    }
+
+    /// Invokes the event without applying the preprocessor, leaving it to `dispatch`
+    /// to call the handlers once the checks of [`MapLocked::invoke_via`] passed.
+    fn invoke_checked<D>(&self, args: TEventArgs, dispatch: D) -> InvokeStats
+    where
+        D: FnOnce(&MapInner<TEventArgs>, TEventArgs) -> (InvokeStats, Vec<HandleKey>),
+    {
+        let short_circuited = InvokeStats {
+            short_circuited: true,
+            ..InvokeStats::default()
+        };
+        if !self.is_enabled() {
+            return short_circuited;
+        }
+        let Ok(args) = self.admit(args) else {
+            return short_circuited;
+        };
+        if !self.is_valid(&args) {
+            return short_circuited;
+        }
+
+        let (stats, unregister_list) = {
+            let handlers = self.current();
+            if handlers.is_empty() || self.is_below_minimum(&handlers) {
+                return short_circuited;
+            }
+            dispatch(&handlers, args)
+        };
+
+        // Clean up after any FnOnce type.
+        self.unregister_all(unregister_list);
+        self.run_deferred();
+        stats
+    }
+
+    fn try_invoke(&self, args: TEventArgs) -> Result<usize, EventInvocationError> {
+        if !self.is_enabled() {
+            return Ok(0);
+        }
+        let args = match self.admit(args) {
+            Ok(args) => args,
+            Err(Reentrancy::Error) => return Err(EventInvocationError::Reentrant),
+            Err(_) => return Ok(0),
+        };
+        if !self.is_valid(&args) {
+            return Err(EventInvocationError::InvalidArgs);
+        }
+
+        let (fired, unregister_list) = {
+            let handlers = self.current();
+            if self.is_below_minimum(&handlers) {
+                return Err(EventInvocationError::BelowMinimum);
+            }
+            if handlers.is_empty() {
+                return Err(EventInvocationError::NoHandlers);
+            }
+            self.dispatch(self.ordered(&handlers), args)
+        };
+
+        // Clean up after any FnOnce type.
+        self.unregister_all(unregister_list);
+        self.run_deferred();
+        Ok(fired)
+    }
+
+    /// Applies the reentrancy policy to an invocation, deferring it if required.
+    ///
+    /// Returns the arguments if the invocation may proceed, or the policy that prevented it.
+    fn admit(&self, args: TEventArgs) -> Result<TEventArgs, Reentrancy> {
+        let policy = *self.reentrancy.lock().unwrap();
+        if policy == Reentrancy::Allow || depth::current_depth(self.address()) == 0 {
+            return Ok(args);
+        }
+        if policy == Reentrancy::Queue {
+            self.reentrant.lock().unwrap().push_back(args);
+        }
+        Err(policy)
+    }
+
+    /// Runs the invocations deferred by [`Reentrancy::Queue`], unless called
+    /// from within another invocation.
+    fn run_deferred(&self) {
+        if depth::current_depth(self.address()) > 0 {
+            return;
+        }
+        loop {
+            // The lock is released before invoking, so the invocation may defer further ones.
+            let Some(args) = self.reentrant.lock().unwrap().pop_front() else {
+                break;
+            };
+            self.invoke_unprocessed(args);
+        }
+    }
+
+    /// Calls the handlers, returning the number of handlers called and
+    /// the keys of the one-shot handlers to unregister.
+    fn dispatch<'a>(
+        &self,
+        handlers: impl IntoIterator<Item = (&'a HandleKey, &'a Arc<Registration<TEventArgs>>)>,
+        args: TEventArgs,
+    ) -> (usize, Vec<HandleKey>)
+    where
+        TEventArgs: 'a,
+    {
+        let (stats, unregister_list) = self.dispatch_with_stats(handlers, args);
+        (stats.fired, unregister_list)
+    }
+
+    /// Calls the handlers like [`MapLocked::dispatch`], additionally reporting
+    /// how often the arguments were cloned.
+    fn dispatch_with_stats<'a>(
+        &self,
+        handlers: impl IntoIterator<Item = (&'a HandleKey, &'a Arc<Registration<TEventArgs>>)>,
+        args: TEventArgs,
+    ) -> (InvokeStats, Vec<HandleKey>)
+    where
+        TEventArgs: 'a,
+    {
+        self.dispatch_via(handlers, args, |_, entry, args| self.call(entry, args))
+    }
+
+    /// Calls the handlers like [`MapLocked::dispatch_with_stats`], calling each handler
+    /// through `call`, which returns whether the handler was called.
+    fn dispatch_via<'a, F>(
+        &self,
+        handlers: impl IntoIterator<Item = (&'a HandleKey, &'a Arc<Registration<TEventArgs>>)>,
+        args: TEventArgs,
+        mut call: F,
+    ) -> (InvokeStats, Vec<HandleKey>)
+    where
+        TEventArgs: 'a,
+        F: FnMut(HandleKey, &Arc<Registration<TEventArgs>>, TEventArgs) -> bool,
+    {
+        if !self.is_enabled() {
+            return (InvokeStats::default(), Vec::new());
+        }
+        let _depth = self.enter();
+        let sequence = self.next_sequence();
+        let mut fired = 0;
+        let mut cloned_args = 0;
+        let mut unregister_list = Vec::new();
+        let mut audited = self.audit.as_ref().map(|_| Vec::new());
+        for (key, entry) in handlers {
+            if !self.is_active(entry, sequence) {
+                continue;
+            }
+            cloned_args += 1;
+            if call(*key, entry, args.clone()) {
+                fired += 1;
+                if let Some(audited) = &mut audited {
+                    audited.push(RegistrationId(*key));
+                }
+            }
+            if entry.handler.is_once() {
+                unregister_list.push(*key);
+            }
+        }
+        if let (Some(log), Some(audited)) = (&self.audit, audited) {
+            log.lock().unwrap().record(audited);
+        }
+
+        let stats = InvokeStats {
+            fired,
+            short_circuited: false,
+            cloned_args,
+        };
+        (stats, unregister_list)
+    }
+
+    /// Calls the handler, or hands the call to the queue or scheduler of the event.
+    ///
+    /// Returns whether the handler was called.
+    fn call(&self, entry: &Arc<Registration<TEventArgs>>, args: TEventArgs) -> bool {
+        match (&self.queue, &self.scheduler) {
+            (Some(queue), _) => {
+                queue.lock().unwrap().push(entry.clone(), args);
+                true
+            }
+            (None, Some(schedule)) => {
+                schedule(entry.clone(), args);
+                true
+            }
+            (None, None) => entry.handler.call(args),
+        }
+    }
+
+    /// Logs a warning if a handler exceeded the configured threshold.
+    #[cfg(feature = "log")]
+    fn warn_if_slow(&self, key: HandleKey, elapsed: Duration) {
+        let threshold = *self.slow_handler_threshold.lock().unwrap();
+        if matches!(threshold, Some(threshold) if elapsed > threshold) {
+            log::warn!(
+                "Event handler {:?} took {:?} to complete",
+                RegistrationId(key),
+                elapsed
+            );
+        }
+    }
+
+    /// Starts an invocation, taking a snapshot of the handlers it calls.
+    ///
+    /// Nothing is taken if the event is disabled, and handlers that are not active for
+    /// the invocation are left out, see [`MapLocked::is_active`]. One-shot handlers are unregistered from the map, as the caller is expected to call them.
+    fn snapshot(&self) -> Vec<(HandleKey, Arc<Registration<TEventArgs>>)> {
+        if !self.is_enabled() {
+            return Vec::new();
+        }
+        let sequence = self.next_sequence();
+        let handlers: Vec<_> = self
+            .read_map()
+            .iter()
+            .filter(|(_, entry)| self.is_active(entry, sequence))
+            .map(|(key, entry)| (*key, entry.clone()))
+            .collect();
+
+        self.unregister_all(
+            handlers
+                .iter()
+                .filter(|(_, entry)| entry.handler.is_once())
+                .map(|(key, _)| *key)
+                .collect(),
+        );
+
+        handlers
+    }
+
+    /// Removes the specified keys from the map.
+    fn unregister_all(&self, keys: Vec<HandleKey>) {
+        if !keys.is_empty() {
+            let removed: Vec<_> = keys.into_iter().filter(|key| self.remove(key)).collect();
+            self.notify(&self.on_unsubscribe, removed);
+        }
+    }
+}
+
+impl<TEventArgs> Deref for MapLocked<TEventArgs> {
+    type Target = RwLock<Arc<MapInner<TEventArgs>>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
+
+impl<TEventArgs> DerefMut for MapLocked<TEventArgs> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.map
+    }
+}
+
+/// Provides the `invoke` function for an event.
+pub trait Invoke<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    /// Invokes the event, returning the number of handlers called.
+    fn invoke(&self, args: TEventArgs) -> usize;
+}
+
+impl<TEventArgs> Invoke<TEventArgs> for Event<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    fn invoke(&self, args: TEventArgs) -> usize {
+        self.invoke(args)
+    }
+}
+
+impl<TEventArgs> Invoke<TEventArgs> for EventHandle<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    fn invoke(&self, args: TEventArgs) -> usize {
+        self.pointer
+            .upgrade()
+            .map_or(0, |ptr| ptr.try_invoke(args).unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dummy(_args: ()) {
+        println!("Dummy called.");
+    }
+
+    #[test]
+    fn new_handler_has_no_registrations() {
+        let handler = Event::<()>::new();
+        assert_eq!(handler.len(), 0);
+    }
+
+    #[test]
+    #[allow(unused_variables)]
+    fn can_add_fn() {
+        let handler = Event::<()>::new();
+        let handle = handler.add_fn(dummy).unwrap();
+        assert_eq!(handler.len(), 1);
+        handler.invoke(());
+    }
+
+    #[test]
+    #[allow(unused_variables)]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn can_add_fnonce() {
+        let handler = Event::new();
+        let handle = handler.add_fnonce(dummy).unwrap();
+        assert_eq!(handler.len(), 1);
+        handler.invoke(());
+        assert_eq!(handler.len(), 0);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn fnonce_is_removed_among_fn_handlers() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let _repeated = event
+            .add_fn({
+                let calls = calls.clone();
+                move |value| calls.lock().unwrap().push(("fn", value))
+            })
+            .unwrap();
+        let _once = event
+            .add_fnonce({
+                let calls = calls.clone();
+                move |value| calls.lock().unwrap().push(("fnonce", value))
+            })
+            .unwrap();
+
+        event.invoke(1);
+        assert_eq!(event.len(), 1);
+        event.invoke(2);
+
+        let mut calls = calls.lock().unwrap().clone();
+        calls.sort();
+        assert_eq!(calls, vec![("fn", 1), ("fn", 2), ("fnonce", 1)]);
+    }
+
+    #[test]
+    #[allow(unused_variables)]
+    fn can_add_function_pointer() {
+        let handler = Event::<()>::new();
+        let handle = handler.add_ptr(dummy).unwrap();
+        assert_eq!(handler.len(), 1);
+        handler.invoke(());
+    }
+
+    #[test]
+    #[allow(unused_variables)]
+    fn cannot_register_same_function_twice() {
+        let handler = Event::new();
+        let handle = handler.add_ptr_unique(dummy).unwrap();
+        assert!(handler.add_ptr_unique(dummy).is_err());
+    }
+
+    #[test]
+    fn same_function_can_be_registered_twice() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        fn count(_: ()) {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let event = Event::new();
+        let first = event.add_ptr(count).unwrap();
+        let second = event.add_ptr(count).unwrap();
+        assert_ne!(first.id(), second.id());
+
+        event.invoke(());
+        assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn same_closure_can_be_registered_twice() {
+        let event = Event::new();
+        let count = Arc::new(Mutex::new(0));
+        let handler = {
+            let count = count.clone();
+            move |_: ()| *count.lock().unwrap() += 1
+        };
+
+        let _first = event.add_fn(handler.clone()).unwrap();
+        let _second = event.add_fn(handler).unwrap();
+        // Stateless closures box to the same dangling address.
+        let _third = event.add_fn(|_: ()| ()).unwrap();
+        let _fourth = event.add_fn(|_: ()| ()).unwrap();
+        assert_eq!(event.len(), 4);
+
+        event.invoke(());
+        assert_eq!(*count.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn same_function_pointer_collides_across_stack_frames() {
+        fn register_nested(
+            event: &Event<()>,
+            depth: usize,
+        ) -> Result<EventHandle<()>, AddHandlerError> {
+            // Occupies stack space so the nested call runs in a different frame.
+            let padding = std::hint::black_box([0u8; 256]);
+            match depth {
+                0 => event.add_ptr_unique(dummy),
+                _ => register_nested(event, depth - 1 + padding[0] as usize),
+            }
+        }
+
+        let event = Event::new();
+        let _handle = event.add_ptr_unique(dummy).unwrap();
+        assert_eq!(
+            register_nested(&event, 3).err(),
+            Some(AddHandlerError::AlreadyRegistered)
+        );
+        assert_eq!(event.len(), 1);
+    }
+
+    #[test]
+    fn can_remove_handlers() {
+        let handler = Event::new();
+        let handle = handler.add_fn(dummy).unwrap();
+        assert_eq!(handler.len(), 1);
+        drop(handle);
+        assert_eq!(handler.len(), 0);
+    }
+
+    #[test]
+    fn invoke_par_timeout_reports_slow_handlers() {
+        let event = Event::new();
+        let fast_delay = Duration::from_millis(0);
+        let slow_delay = Duration::from_millis(500);
+        let fast = event
+            .add_fn(move |_: ()| thread::sleep(fast_delay))
+            .unwrap();
+        let slow = event
+            .add_fn(move |_: ()| thread::sleep(slow_delay))
+            .unwrap();
+
+        let timed_out = event.invoke_par_timeout((), Duration::from_millis(100));
+        assert_eq!(timed_out, vec![slow.id()]);
+        assert!(!timed_out.contains(&fast.id()));
+    }
+
+    #[test]
+    fn frozen_event_rejects_registrations() {
+        let event = Event::new();
+        let count = Arc::new(Mutex::new(0));
+        let first = event
+            .add_fn({
+                let count = count.clone();
+                move |_: ()| *count.lock().unwrap() += 1
+            })
+            .unwrap();
+        let second = event
+            .add_fn({
+                let count = count.clone();
+                move |_: ()| *count.lock().unwrap() += 1
+            })
+            .unwrap();
+
+        event.freeze();
+        assert!(event.is_frozen());
+        assert_eq!(event.add_ptr(dummy).err(), Some(AddHandlerError::Frozen));
+
+        event.invoke(());
+        assert_eq!(*count.lock().unwrap(), 2);
+
+        drop(first);
+        drop(second);
+        assert_eq!(event.len(), 0);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn invoking_without_handlers_fails() {
+        let event = Event::new();
+        assert_eq!(event.try_invoke(()), Err(EventInvocationError::NoHandlers));
+
+        let handle = event.add_fnonce(dummy).unwrap();
+        assert_eq!(handle.invoke(()), Ok(()));
+        assert_eq!(handle.invoke(()), Err(EventInvocationError::NoHandlers));
+    }
+
+    #[test]
+    fn poisoned_event_remains_usable() {
+        let event = Arc::new(Event::new());
+        let poisoner = event.clone();
+        assert!(std::thread::spawn(move || {
+            let _guard = poisoner.handlers.write().unwrap();
+            panic!("poisoning the lock");
+        })
+        .join()
+        .is_err());
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handle = {
+            let calls = calls.clone();
+            event.add_fn(move |_: ()| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+        assert_eq!(event.invoke(()), 1);
+        assert_eq!(event.try_invoke(()), Ok(()));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert!(handle.unregister());
+        assert!(event.is_empty());
+    }
+
+    #[test]
+    fn panicking_fnmut_handler_does_not_poison_later_invocations() {
+        let event = Event::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let _handle = {
+            let calls = calls.clone();
+            event.add_fnmut(move |fail: bool| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                assert!(!fail, "handler failed");
+            })
+        }
+        .unwrap();
+
+        assert_eq!(event.invoke_catching(true).len(), 1);
+        assert_eq!(event.invoke(false), 1);
+        assert!(event.invoke_catching(false).is_empty());
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn invoking_below_minimum_fails() {
+        let event = Event::new();
+        event.set_min_handlers(1);
+        assert_eq!(
+            event.try_invoke(()),
+            Err(EventInvocationError::BelowMinimum)
+        );
+
+        let handle = event.add_fnonce(dummy).unwrap();
+        assert_eq!(event.try_invoke(()), Ok(()));
+        assert_eq!(handle.invoke(()), Err(EventInvocationError::BelowMinimum));
+    }
+
+    #[test]
+    fn invoke_with_order_fires_listed_handlers_in_order() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: ()| calls.lock().unwrap().push(name)
+        };
+        let a = event.add_fn(recorder("a")).unwrap();
+        let _b = event.add_fn(recorder("b")).unwrap();
+        let c = event.add_fn(recorder("c")).unwrap();
+
+        event.invoke_with_order((), &[c.id(), a.id()]);
+        assert_eq!(*calls.lock().unwrap(), vec!["c", "a"]);
+    }
+
+    #[test]
+    fn invoke_with_order_applies_the_entry_checks() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| calls.lock().unwrap().push((name, value))
+        };
+        let a = event.add_fn(recorder("a")).unwrap();
+        let b = event.add_fn(recorder("b")).unwrap();
+        event.set_try_preprocessor(|value: i32| Ok::<_, ()>(value * 10));
+        event.set_validator(|value: &i32| *value > 0);
+
+        event.invoke_with_order(0, &[b.id(), a.id()]);
+        assert!(calls.lock().unwrap().is_empty());
+
+        event.invoke_with_order(1, &[b.id(), a.id()]);
+        assert_eq!(*calls.lock().unwrap(), vec![("b", 10), ("a", 10)]);
+    }
+
+    #[test]
+    fn invoke_to_fires_only_the_addressed_handler() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: ()| calls.lock().unwrap().push(name)
+        };
+        let _a = event.add_fn(recorder("a")).unwrap();
+        let b = event.add_fn(recorder("b")).unwrap();
+        let _c = event.add_fn(recorder("c")).unwrap();
+
+        assert_eq!(event.invoke_to(b.id(), ()), Ok(()));
+        assert_eq!(*calls.lock().unwrap(), vec!["b"]);
+
+        let id = b.id();
+        drop(b);
+        assert_eq!(
+            event.invoke_to(id, ()),
+            Err(EventInvocationError::NoSuchHandler)
+        );
+        assert_eq!(*calls.lock().unwrap(), vec!["b"]);
+    }
+
+    #[test]
+    fn invoke_to_applies_the_entry_checks() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let target = {
+            let calls = calls.clone();
+            event.add_fn(move |value: i32| calls.lock().unwrap().push(value))
+        }
+        .unwrap();
+        event.set_try_preprocessor(|value: i32| Ok::<_, ()>(value * 10));
+        event.set_validator(|value: &i32| *value > 0);
+
+        assert_eq!(event.invoke_to(target.id(), 0), Ok(()));
+        assert!(calls.lock().unwrap().is_empty());
+
+        assert_eq!(event.invoke_to(target.id(), 1), Ok(()));
+        assert_eq!(*calls.lock().unwrap(), vec![10]);
+    }
+
+    #[test]
+    fn add_fn_unique_rejects_equal_keys() {
+        let event = Event::new();
+        let count = Arc::new(Mutex::new(0));
+        let handler = {
+            let count = count.clone();
+            move |_: ()| *count.lock().unwrap() += 1
+        };
+
+        let _handle = event.add_fn_unique("counter", handler.clone()).unwrap();
+        assert_eq!(
+            event.add_fn_unique("counter", handler).err(),
+            Some(AddHandlerError::AlreadyRegistered)
+        );
+        // Keys of different types are distinct.
+        assert!(event.add_fn_unique(String::from("counter"), |_| ()).is_ok());
+
+        let other = {
+            let count = count.clone();
+            move |_: ()| *count.lock().unwrap() += 10
+        };
+        let _other = event.add_fn_unique("other", other).unwrap();
+
+        event.invoke(());
+        assert_eq!(*count.lock().unwrap(), 11);
+    }
+
+    #[test]
+    fn handler_can_reraise_through_weak_self() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let _handle = event
+            .add_fn_with_weak_self({
+                let calls = calls.clone();
+                move |event, remaining: u32| {
+                    calls.lock().unwrap().push(remaining);
+                    if remaining > 0 {
+                        event.invoke(remaining - 1).unwrap();
+                    }
+                }
+            })
+            .unwrap();
+
+        event.invoke(2);
+        assert_eq!(*calls.lock().unwrap(), vec![2, 1, 0]);
+
+        let weak = event.downgrade();
+        drop(event);
+        assert!(!weak.is_alive());
+    }
+
+    #[test]
+    #[cfg(feature = "backtrace")]
+    fn registration_site_is_captured() {
+        let event = Event::<()>::new();
+        let handle = event.add_ptr(dummy).unwrap();
+        let site = event.registration_site(handle.id()).unwrap();
+        assert!(!site.is_empty());
+
+        let id = handle.id();
+        drop(handle);
+        assert_eq!(event.registration_site(id), None);
+    }
+
+    #[test]
+    fn try_replace_all_checks_expected_len() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            Box::new(move |_: ()| calls.lock().unwrap().push(name))
+        };
+        let _old = event.add_fn(recorder("old")).unwrap();
+
+        assert_eq!(event.try_replace_all(2, vec![recorder("new")]), Err(1));
+        assert_eq!(
+            event.try_replace_all(1, vec![recorder("a"), recorder("b")]),
+            Ok(())
+        );
+        assert_eq!(event.len(), 2);
+
+        event.invoke(());
+        let mut calls = calls.lock().unwrap().clone();
+        calls.sort();
+        assert_eq!(calls, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn invoke_timed_reports_each_handler() {
+        let event = Event::new();
+        let first = event.add_ptr(dummy).unwrap();
+        let second = event.add_fn(|_: ()| ()).unwrap();
+
+        let timings = event.invoke_timed(());
+        let mut ids: Vec<_> = timings.iter().map(|(id, _)| *id).collect();
+        ids.sort();
+        let mut expected = vec![first.id(), second.id()];
+        expected.sort();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn invoke_timed_dispatches_like_invoke() {
+        let event = Event::new();
+        let late = event.add_fn(|_: i32| ()).unwrap();
+        let early = event.add_fn_with_priority(|_: i32| (), -1).unwrap();
+        let blocked = event.add_fn(|_: i32| ()).unwrap();
+        blocked.set_blocked(true);
+        event.set_validator(|value: &i32| *value >= 0);
+
+        let ids: Vec<_> = event
+            .invoke_timed(1)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, vec![early.id(), late.id()]);
+        assert!(event.invoke_timed(-1).is_empty());
+
+        event.set_min_handlers(4);
+        assert!(event.invoke_timed(1).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "log")]
+    fn invoke_timed_warns_about_slow_handlers() {
+        struct CapturingLogger(Mutex<Vec<String>>);
+
+        impl log::Log for CapturingLogger {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+
+            fn log(&self, record: &log::Record) {
+                if self.enabled(record.metadata()) {
+                    self.0.lock().unwrap().push(record.args().to_string());
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static LOGGER: CapturingLogger = CapturingLogger(Mutex::new(Vec::new()));
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+
+        let event = Event::new();
+        let delay = Duration::from_millis(20);
+        let slow = event.add_fn(move |_: ()| thread::sleep(delay)).unwrap();
+        event.set_slow_handler_threshold(Some(Duration::from_millis(5)));
+
+        event.invoke_timed(());
+        let messages = LOGGER.0.lock().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].contains(&format!("{:?}", slow.id())));
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn invoke_tagged_only_consumes_fnonce_of_group() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: ()| calls.lock().unwrap().push(name)
+        };
+        let _first = event.add_fnonce_tagged(1, recorder("first")).unwrap();
+        let _second = event.add_fnonce_tagged(2, recorder("second")).unwrap();
+
+        event.invoke_tagged(1, ());
+        assert_eq!(*calls.lock().unwrap(), vec!["first"]);
+        assert_eq!(event.len(), 1);
+
+        event.invoke_tagged(2, ());
+        assert_eq!(*calls.lock().unwrap(), vec!["first", "second"]);
+        assert_eq!(event.len(), 0);
+    }
+
+    #[test]
+    fn invoke_tagged_dispatches_like_invoke() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| calls.lock().unwrap().push((name, value))
+        };
+        let _first = event.add_fn_tagged(1, recorder("first")).unwrap();
+        let _other = event.add_fn_tagged(2, recorder("other")).unwrap();
+        let _second = event.add_fn_tagged(1, recorder("second")).unwrap();
+        // Calls the latest registration first.
+        event.set_order_comparator(|a, b| b.id.cmp(&a.id));
+        event.set_validator(|value: &i32| *value > 0);
+
+        event.invoke_tagged(1, 0);
+        assert!(calls.lock().unwrap().is_empty());
+
+        event.invoke_tagged(1, 1);
+        assert_eq!(*calls.lock().unwrap(), vec![("second", 1), ("first", 1)]);
+    }
+
+    #[test]
+    fn invoke_topic_only_fires_handlers_of_the_topic() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: (&'static str, u32)| calls.lock().unwrap().push(name)
+        };
+        let _a = event
+            .add_fn_for_topic("a".to_string(), recorder("a"))
+            .unwrap();
+        let _b = event
+            .add_fn_for_topic("b".to_string(), recorder("b"))
+            .unwrap();
+
+        event.invoke_topic(("a", 1), |(topic, _)| topic.to_string());
+        assert_eq!(*calls.lock().unwrap(), vec!["a"]);
+    }
+
+    #[test]
+    fn invoke_topic_dispatches_like_invoke() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |(_, value): (&'static str, u32)| calls.lock().unwrap().push((name, value))
+        };
+        let topic = |name: &str| name.to_string();
+        let _first = event
+            .add_fn_for_topic(topic("a"), recorder("first"))
+            .unwrap();
+        let _other = event
+            .add_fn_for_topic(topic("b"), recorder("other"))
+            .unwrap();
+        let _second = event
+            .add_fn_for_topic(topic("a"), recorder("second"))
+            .unwrap();
+        // Calls the latest registration first.
+        event.set_order_comparator(|a, b| b.id.cmp(&a.id));
+        event.set_validator(|(_, value): &(&str, u32)| *value > 0);
+
+        event.invoke_topic(("a", 0), |(name, _)| topic(name));
+        assert!(calls.lock().unwrap().is_empty());
+
+        event.invoke_topic(("a", 1), |(name, _)| topic(name));
+        assert_eq!(*calls.lock().unwrap(), vec![("second", 1), ("first", 1)]);
+    }
+
+    #[test]
+    fn invoke_try_build_propagates_errors() {
+        let event = Event::new();
+        assert_eq!(
+            event.invoke_try_build(|| -> Result<(), &str> { panic!("must not be built") }),
+            Ok(0)
+        );
+
+        let count = Arc::new(Mutex::new(0));
+        let _handle = event
+            .add_fn({
+                let count = count.clone();
+                move |_: ()| *count.lock().unwrap() += 1
+            })
+            .unwrap();
+
+        assert_eq!(
+            event.invoke_try_build(|| Err("parse error")),
+            Err("parse error")
+        );
+        assert_eq!(*count.lock().unwrap(), 0);
+
+        assert_eq!(event.invoke_try_build(|| Ok::<_, &str>(())), Ok(1));
+        assert_eq!(*count.lock().unwrap(), 1);
+    }
+
+    #[test]
+    fn trace_id_is_propagated_through_forwarding() {
+        let source = Event::new();
+        let target = Event::new();
+        let _forwarding = source.forward_to(&target).unwrap();
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let _handle = target
+            .add_fn_traced({
+                let received = received.clone();
+                move |trace_id, value: i32| received.lock().unwrap().push((trace_id, value))
+            })
+            .unwrap();
+
+        source.invoke_traced_id(1234, 1);
+        source.invoke(2);
+        assert_eq!(*received.lock().unwrap(), vec![(1234, 1), (UNTRACED, 2)]);
+    }
+
+    #[test]
+    #[cfg(feature = "allocator")]
+    fn handlers_are_boxed_by_custom_allocator() {
+        use std::alloc::{GlobalAlloc, Layout, System};
+        use std::sync::atomic::AtomicIsize;
+
+        struct CountingAllocator(Arc<AtomicIsize>);
+
+        unsafe impl HandlerAllocator for CountingAllocator {
+            unsafe fn allocate(&self, layout: Layout) -> *mut u8 {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                System.alloc(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout) {
+                self.0.fetch_sub(1, Ordering::SeqCst);
+                System.dealloc(ptr, layout)
+            }
+        }
+
+        let live = Arc::new(AtomicIsize::new(0));
+        let event = Event::with_allocator(CountingAllocator(live.clone()));
+        let count = Arc::new(Mutex::new(0));
+        let handles: Vec<_> = (1..=2)
+            .map(|amount| {
+                let count = count.clone();
+                event
+                    .add_fn(move |_: ()| *count.lock().unwrap() += amount)
+                    .unwrap()
+            })
+            .collect();
+        let unique = {
+            let count = count.clone();
+            event.add_fn_unique("unique", move |_: ()| *count.lock().unwrap() += 4)
+        }
+        .unwrap();
+        assert_eq!(live.load(Ordering::SeqCst), 3);
+
+        event.invoke(());
+        assert_eq!(*count.lock().unwrap(), 7);
+
+        drop((handles, unique));
+        assert_eq!(live.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "tokio")]
+    async fn broadcast_channel_delivers_to_all_receivers() {
+        let event = Event::new();
+        let mut first = event.broadcast_channel(4).unwrap();
+        let mut second = first.resubscribe();
+
+        event.invoke(1);
+        event.invoke(2);
+
+        for receiver in [&mut first, &mut second] {
+            assert_eq!(receiver.recv().await.unwrap(), 1);
+            assert_eq!(receiver.recv().await.unwrap(), 2);
+        }
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "futures")]
+    async fn async_invocations_skip_inactive_handlers() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| {
+                calls.lock().unwrap().push((name, value));
+                async {}
+            }
+        };
+        let _active = event.add_async_fn(recorder("active")).unwrap();
+        let blocked = event.add_async_fn(recorder("blocked")).unwrap();
+        blocked.set_blocked(true);
+
+        event.invoke_async(1).await;
+        event.invoke_async_ordered(2).await;
+        event.set_enabled(false);
+        event.invoke_async(3).await;
+
+        assert_eq!(*calls.lock().unwrap(), vec![("active", 1), ("active", 2)]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "futures")]
+    async fn next_resolves_on_the_next_invocation() {
+        let event = Arc::new(Event::new());
+        let next = tokio::spawn(event.next().unwrap());
+        tokio::spawn({
+            let event = event.clone();
+            async move {
+                event.invoke(1);
+                event.invoke(2);
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(next.await.unwrap(), Some(1));
+        assert_eq!(event.len(), 0);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "futures")]
+    async fn next_resolves_to_none_once_the_event_is_dropped() {
+        let event = Event::<u32>::new();
+        let next = event.next().unwrap();
+        drop(event);
+        assert_eq!(next.await, None);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "futures")]
+    async fn subscribe_stream_yields_every_invocation() {
+        use futures::StreamExt;
+
+        let event = Event::new();
+        let stream = event.subscribe_stream().unwrap();
+        for value in 1..=3 {
+            event.invoke(value);
+        }
+        drop(event);
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![1, 2, 3]);
+
+        let event = Event::new();
+        let stream = event.subscribe_stream().unwrap();
+        assert_eq!(event.len(), 1);
+        drop(stream);
+        assert_eq!(event.len(), 0);
+        assert_eq!(event.invoke(1), 0);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "futures")]
+    async fn subscribe_stream_bounded_drops_overflowing_arguments() {
+        use futures::StreamExt;
+
+        let event = Event::new();
+        let mut stream = event.subscribe_stream_bounded(2).unwrap();
+        for value in 1..=3 {
+            event.invoke(value);
+        }
+        assert_eq!(stream.next().await, Some(1));
+        event.invoke(4);
+        drop(event);
+        assert_eq!(stream.collect::<Vec<_>>().await, vec![2, 4]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "futures")]
+    async fn invoke_async_awaits_mutable_async_handlers() {
+        let event = Event::new();
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let _handle = event
+            .add_async_fn({
+                let observed = observed.clone();
+                let mut calls = 0;
+                move |value: i32| {
+                    calls += 1;
+                    let call = calls;
+                    let observed = observed.clone();
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(10)).await;
+                        observed.lock().unwrap().push((call, value));
+                    }
+                }
+            })
+            .unwrap();
+
+        event.invoke(0);
+        assert!(observed.lock().unwrap().is_empty());
+
+        event.invoke_async(1).await;
+        event.invoke_async(2).await;
+        assert_eq!(*observed.lock().unwrap(), vec![(1, 1), (2, 2)]);
+    }
+
+    #[tokio::test]
+    #[cfg(feature = "futures")]
+    async fn invoke_async_ordered_awaits_by_priority() {
+        let event = Event::new();
+        let completed = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |priority: i32| {
+            let completed = completed.clone();
+            move |_: ()| {
+                let completed = completed.clone();
+                async move {
+                    // Handlers called first sleep longest to detect concurrent execution.
+                    let delay = Duration::from_millis((10 - priority) as u64 * 2);
+                    tokio::time::sleep(delay).await;
+                    completed.lock().unwrap().push(priority);
+                }
+            }
+        };
+
+        let _five = event.add_async_with_priority(5, recorder(5)).unwrap();
+        let _minus_one = event.add_async_with_priority(-1, recorder(-1)).unwrap();
+        let _zero = event.add_async_with_priority(0, recorder(0)).unwrap();
+
+        event.invoke(());
+        assert!(completed.lock().unwrap().is_empty());
+
+        event.invoke_async_ordered(()).await;
+        assert_eq!(*completed.lock().unwrap(), vec![-1, 0, 5]);
+    }
+
+    #[test]
+    fn invoke_panic_safe_reports_panics() {
+        let event = Event::new();
+        let count = Arc::new(Mutex::new(0));
+        let _before = event
+            .add_fn({
+                let count = count.clone();
+                move |_: ()| *count.lock().unwrap() += 1
+            })
+            .unwrap();
+        let panicking = event
+            .add_fn(|_: ()| panic!("handler failed with code {}", 42))
+            .unwrap();
+        let _after = event
+            .add_fn({
+                let count = count.clone();
+                move |_: ()| *count.lock().unwrap() += 1
+            })
+            .unwrap();
+
+        let failures = event.invoke_panic_safe(());
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].id, panicking.id());
+        assert!(failures[0].message.contains("handler failed with code 42"));
+        assert_eq!(*count.lock().unwrap(), 2);
+
+        assert!(!event.handlers.is_poisoned());
+    }
+
+    #[test]
+    fn invoke_panic_safe_dispatches_like_invoke() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| calls.lock().unwrap().push((name, value))
+        };
+        let _late = event.add_fn_with_priority(recorder("late"), 1).unwrap();
+        let panicking = event
+            .add_fn_with_priority(|_: i32| panic!("boom"), 0)
+            .unwrap();
+        let _early = event.add_fn_with_priority(recorder("early"), -1).unwrap();
+        event.set_validator(|value: &i32| *value > 0);
+
+        assert!(event.invoke_panic_safe(0).is_empty());
+        assert!(calls.lock().unwrap().is_empty());
+
+        let failures = event.invoke_panic_safe(1);
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].id, panicking.id());
+        assert_eq!(*calls.lock().unwrap(), vec![("early", 1), ("late", 1)]);
+    }
+
+    #[test]
+    fn deferred_closures_run_after_all_handlers() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let handlers: Vec<_> = ["first", "second"]
+            .into_iter()
+            .map(|name| {
+                let calls = calls.clone();
+                event
+                    .add_fn_with_context(move |context, _: ()| {
+                        calls.lock().unwrap().push(format!("{name} handler"));
+                        let calls = calls.clone();
+                        context
+                            .defer(move || calls.lock().unwrap().push(format!("{name} deferred")));
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        event.invoke_deferred(());
+
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 4);
+        assert!(calls[..2].iter().all(|call| call.ends_with("handler")));
+        let handler_order: Vec<_> = calls[..2]
+            .iter()
+            .map(|c| c.replace(" handler", ""))
+            .collect();
+        let deferred_order: Vec<_> = calls[2..]
+            .iter()
+            .map(|c| c.replace(" deferred", ""))
+            .collect();
+        assert_eq!(handler_order, deferred_order);
+        drop(handlers);
+    }
+
+    #[test]
+    fn ordered_and_unordered_storage_fire_all_handlers() {
+        for event in [Event::ordered(), Event::unordered()] {
+            let calls = Arc::new(Mutex::new(Vec::new()));
+            let handles: Vec<_> = (0..10)
+                .map(|i| {
+                    let calls = calls.clone();
+                    event
+                        .add_fn(move |_: ()| calls.lock().unwrap().push(i))
+                        .unwrap()
+                })
+                .collect();
+            assert_eq!(event.len(), 10);
+
+            event.invoke(());
+            let mut calls = calls.lock().unwrap().clone();
+            calls.sort();
+            assert_eq!(calls, (0..10).collect::<Vec<_>>());
+            drop(handles);
+            assert_eq!(event.len(), 0);
+        }
+    }
+
+    #[test]
+    fn ordered_storage_fires_in_registration_order() {
+        let event = Event::ordered();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let handles: Vec<_> = (0..10)
+            .map(|_| {
+                let calls = calls.clone();
+                let id = Arc::new(Mutex::new(None));
+                let handle = event
+                    .add_fn({
+                        let id = id.clone();
+                        move |_: ()| calls.lock().unwrap().push(id.lock().unwrap().unwrap())
+                    })
+                    .unwrap();
+                *id.lock().unwrap() = Some(handle.id());
+                handle
+            })
+            .collect();
+
+        event.invoke(());
+        let ids: Vec<_> = handles.iter().map(EventHandle::id).collect();
+        assert_eq!(*calls.lock().unwrap(), ids);
+    }
+
+    #[test]
+    fn invoke_calls_handlers_in_registration_order() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: ()| calls.lock().unwrap().push(name)
+        };
+        let _c = event.add_fn(recorder("c")).unwrap();
+        let _a = event.add_fn(recorder("a")).unwrap();
+        let _b = event.add_fn(recorder("b")).unwrap();
+
+        for _ in 0..3 {
+            event.invoke(());
+        }
+        assert_eq!(*calls.lock().unwrap(), ["c", "a", "b"].repeat(3));
+    }
+
+    #[test]
+    fn concurrent_identical_invocations_are_coalesced() {
+        let event = Arc::new(Event::new());
+        let count = Arc::new(Mutex::new(0));
+        let (started, started_rx) = mpsc::channel();
+        let (release, release_rx) = mpsc::channel::<()>();
+        let release_rx = Mutex::new(release_rx);
+        let _handle = event
+            .add_fn({
+                let count = count.clone();
+                move |_: u32| {
+                    *count.lock().unwrap() += 1;
+                    started.send(()).unwrap();
+                    release_rx.lock().unwrap().recv().unwrap();
+                }
+            })
+            .unwrap();
+
+        let first = {
+            let event = event.clone();
+            thread::spawn(move || event.invoke_coalesce_inflight(7))
+        };
+        started_rx.recv().unwrap();
+
+        // The first invocation is blocked in the handler, so these are coalesced into it.
+        let coalesced: Vec<_> = (0..3)
+            .map(|_| {
+                let event = event.clone();
+                thread::spawn(move || event.invoke_coalesce_inflight(7))
+            })
+            .collect();
+        for thread in coalesced {
+            assert!(!thread.join().unwrap());
+        }
+
+        release.send(()).unwrap();
+        assert!(first.join().unwrap());
+        assert_eq!(*count.lock().unwrap(), 1);
+
+        release.send(()).unwrap();
+        assert!(event.invoke_coalesce_inflight(7));
+        assert_eq!(*count.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn current_depth_reflects_nested_invocations() {
+        let event = Event::new();
+        let depths = Arc::new(Mutex::new(Vec::new()));
+        let _handle = event
+            .add_fn_with_weak_self({
+                let depths = depths.clone();
+                move |weak, reraise: bool| {
+                    depths.lock().unwrap().push(weak.current_depth());
+                    if reraise {
+                        weak.invoke(false).unwrap();
+                    }
+                }
+            })
+            .unwrap();
+
+        assert_eq!(event.current_depth(), 0);
+        event.invoke(true);
+        assert_eq!(*depths.lock().unwrap(), vec![1, 2]);
+        assert_eq!(event.current_depth(), 0);
+    }
+
+    /// Registers a handler recording its invocations that re-raises the event once,
+    /// returning the results of the nested invocations.
+    fn reentrant_calls(policy: Reentrancy) -> (Vec<String>, Vec<Result<(), EventInvocationError>>) {
+        let event = Event::new();
+        event.set_reentrancy(policy);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let results = Arc::new(Mutex::new(Vec::new()));
+        let _handle = event
+            .add_fn_with_weak_self({
+                let (calls, results) = (calls.clone(), results.clone());
+                move |weak, value: u32| {
+                    calls.lock().unwrap().push(format!("start {value}"));
+                    if value == 1 {
+                        results.lock().unwrap().push(weak.invoke(2));
+                    }
+                    calls.lock().unwrap().push(format!("end {value}"));
+                }
+            })
+            .unwrap();
+
+        event.invoke(1);
+        let calls = calls.lock().unwrap().clone();
+        let results = results.lock().unwrap().drain(..).collect();
+        (calls, results)
+    }
+
+    #[test]
+    fn reentrancy_policies_treat_nested_invocations() {
+        assert_eq!(
+            reentrant_calls(Reentrancy::Allow),
+            (
+                vec!["start 1", "start 2", "end 2", "end 1"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                vec![Ok(())]
+            )
+        );
+        assert_eq!(
+            reentrant_calls(Reentrancy::Queue),
+            (
+                vec!["start 1", "end 1", "start 2", "end 2"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+                vec![Ok(())]
+            )
+        );
+        assert_eq!(
+            reentrant_calls(Reentrancy::Ignore),
+            (
+                vec!["start 1".to_string(), "end 1".to_string()],
+                vec![Ok(())]
+            )
+        );
+        assert_eq!(
+            reentrant_calls(Reentrancy::Error),
+            (
+                vec!["start 1".to_string(), "end 1".to_string()],
+                vec![Err(EventInvocationError::Reentrant)]
+            )
+        );
+    }
+
+    #[test]
+    fn failing_preprocessor_fires_no_handlers() {
+        let event = Event::new();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let _handle = event
+            .add_fn({
+                let received = received.clone();
+                move |value: i32| received.lock().unwrap().push(value)
+            })
+            .unwrap();
+        event.set_try_preprocessor(|value: i32| {
+            if value < 0 {
+                Err(format!("negative value {value}"))
+            } else {
+                Ok(value * 10)
+            }
+        });
+
+        assert_eq!(
+            event.invoke_try::<String>(-1),
+            Err(PreprocessError::Rejected(String::from("negative value -1")))
+        );
+        assert_eq!(
+            event.invoke_try::<&str>(-1),
+            Err(PreprocessError::OtherType)
+        );
+        assert!(received.lock().unwrap().is_empty());
+
+        assert_eq!(event.invoke_try::<String>(3), Ok(1));
+        event.invoke(-2);
+        event.invoke(4);
+        assert_eq!(*received.lock().unwrap(), vec![30, 40]);
+    }
+
+    #[test]
+    fn handlers_contribute_to_accumulator() {
+        let event = Event::new();
+        let handles: Vec<_> = ["first", "second", "third"]
+            .into_iter()
+            .map(|name| {
+                event
+                    .add_fn_acc(move |names: &mut Vec<String>, suffix: &'static str| {
+                        names.push(format!("{name}{suffix}"))
+                    })
+                    .unwrap()
+            })
+            .collect();
+        assert_eq!(handles.len(), 3);
+
+        let names = Mutex::new(Vec::<String>::new());
+        event.invoke_with_accumulator("!", &names);
+        event.invoke("?");
+
+        let mut names = names.into_inner().unwrap();
+        names.sort();
+        assert_eq!(names, vec!["first!", "second!", "third!"]);
+    }
+
+    #[test]
+    fn invoke_total_deadline_stops_when_budget_is_exhausted() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let _fast = {
+            let calls = calls.clone();
+            event.add_fn(move |value: i32| calls.lock().unwrap().push(("fast", value)))
+        }
+        .unwrap();
+        // Runs first, and far beyond the budget.
+        let _slow = {
+            let calls = calls.clone();
+            event.add_fn_with_priority(
+                move |value: i32| {
+                    calls.lock().unwrap().push(("slow", value));
+                    thread::sleep(Duration::from_millis(250));
+                },
+                -1,
+            )
+        }
+        .unwrap();
+        event.set_validator(|value: &i32| *value > 0);
+
+        assert_eq!(event.invoke_total_deadline(0, Duration::from_secs(60)), 0);
+        assert_eq!(event.invoke_total_deadline(1, Duration::from_millis(50)), 1);
+        assert_eq!(*calls.lock().unwrap(), vec![("slow", 1)]);
+    }
+
+    #[test]
+    fn keyed_subscriptions_are_owned_by_the_event() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        for name in ["a", "b"] {
+            let calls = calls.clone();
+            event
+                .subscribe_into(name, move |_: ()| calls.lock().unwrap().push(name))
+                .unwrap();
+        }
+
+        event.invoke(());
+        calls.lock().unwrap().sort();
+        assert_eq!(*calls.lock().unwrap(), vec!["a", "b"]);
+
+        assert!(event.unsubscribe_key(&"a"));
+        assert!(!event.unsubscribe_key(&"a"));
+        calls.lock().unwrap().clear();
+        event.invoke(());
+        assert_eq!(*calls.lock().unwrap(), vec!["b"]);
+    }
+
+    #[test]
+    fn topological_invoke_honors_dependencies() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: ()| calls.lock().unwrap().push(name)
+        };
+        // The priority makes A run second in a regular invocation.
+        let a = event.add_fn_with_priority(recorder("a"), 1).unwrap();
+        let _b = event.add_fn_after(a.id(), recorder("b")).unwrap();
+
+        event.invoke(());
+        assert_eq!(*calls.lock().unwrap(), vec!["b", "a"]);
+
+        calls.lock().unwrap().clear();
+        assert_eq!(event.invoke_topological(()), Ok(()));
+        assert_eq!(*calls.lock().unwrap(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn topological_invoke_rejects_cycles() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: ()| calls.lock().unwrap().push(name)
+        };
+        let a = event.add_fn(recorder("a")).unwrap();
+        let b = event.add_fn_after(a.id(), recorder("b")).unwrap();
+        let _c = event.add_fn(recorder("c")).unwrap();
+
+        assert!(event.set_after(a.id(), b.id()));
+        assert_eq!(
+            event.invoke_topological(()),
+            Err(EventInvocationError::DependencyCycle)
+        );
+        assert!(calls.lock().unwrap().is_empty());
+
+        drop(b);
+        assert_eq!(event.invoke_topological(()), Ok(()));
+        assert_eq!(*calls.lock().unwrap(), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn bind_first_fixes_the_first_argument() {
+        let event = Event::new();
+        let received = Arc::new(Mutex::new(None));
+        let _handle = {
+            let received = received.clone();
+            event.add_fn(move |args: (i32, i32)| *received.lock().unwrap() = Some(args))
+        }
+        .unwrap();
+
+        let bound = event.bind_first(10);
+        bound(5);
+        assert_eq!(*received.lock().unwrap(), Some((10, 5)));
+    }
+
+    #[test]
+    fn audit_log_keeps_the_most_recent_invocations() {
+        let event = Event::with_audit_capacity(3);
+        let handle = event.add_fn(|_: ()| {}).unwrap();
+        for _ in 0..5 {
+            event.invoke(());
+        }
+
+        let log = event.audit_log();
+        let sequences: Vec<_> = log.iter().map(|entry| entry.sequence).collect();
+        assert_eq!(sequences, vec![2, 3, 4]);
+        assert!(log.iter().all(|entry| entry.fired == vec![handle.id()]));
+        assert!(log[0].timestamp <= log[2].timestamp);
+    }
+
+    #[test]
+    fn scoped_subscription_is_removed_after_body() {
+        let event = Event::new();
+        let count = Arc::new(AtomicUsize::new(0));
+
+        let counter = count.clone();
+        let result = event
+            .with_subscription(
+                move |_: ()| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                },
+                || {
+                    event.invoke(());
+                    42
+                },
+            )
+            .unwrap();
+        event.invoke(());
+
+        assert_eq!(result, 42);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+        assert_eq!(event.len(), 0);
+    }
+
+    #[test]
+    fn overflow_callback_fires_when_capacity_is_exceeded() {
+        let event = Event::new();
+        let overflows = Arc::new(AtomicUsize::new(0));
+        event.set_max_handlers(1);
+        event.set_on_overflow({
+            let overflows = overflows.clone();
+            move || {
+                overflows.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        let _first = event.add_fn(|_: ()| {}).unwrap();
+        let second = event.add_fn(|_: ()| {});
+        assert_eq!(second.err(), Some(AddHandlerError::CapacityExceeded));
+        assert_eq!(overflows.load(Ordering::SeqCst), 1);
+        assert_eq!(event.len(), 1);
+    }
+
+    #[test]
+    fn scheduler_runs_handler_calls() {
+        struct Inline;
+        impl Scheduler for Inline {
+            fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+                task()
+            }
+        }
+
+        type Queue = Arc<Mutex<Vec<Box<dyn FnOnce() + Send>>>>;
+        struct Collecting(Queue);
+        impl Scheduler for Collecting {
+            fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+                self.0.lock().unwrap().push(task)
+            }
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = |count: &Arc<AtomicUsize>| {
+            let count = count.clone();
+            move |amount: usize| {
+                count.fetch_add(amount, Ordering::SeqCst);
+            }
+        };
+
+        let inline = Event::with_scheduler(Inline);
+        let _inline = inline.add_fn(counter(&count)).unwrap();
+        inline.invoke(1);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let queue = Queue::default();
+        let collecting = Event::with_scheduler(Collecting(queue.clone()));
+        let _collecting = collecting.add_fn(counter(&count)).unwrap();
+        collecting.invoke(10);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let tasks = std::mem::take(&mut *queue.lock().unwrap());
+        tasks.into_iter().for_each(|task| task());
+        assert_eq!(count.load(Ordering::SeqCst), 11);
+    }
+
+    #[test]
+    fn work_stealing_calls_every_handler_once() {
+        let event = Event::new();
+        let calls: Arc<Vec<_>> = Arc::new((0..10).map(|_| AtomicUsize::new(0)).collect());
+        let _handles: Vec<_> = (0..calls.len())
+            .map(|index| {
+                let calls = calls.clone();
+                event.add_fn(move |_: ()| {
+                    calls[index].fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let stats = event.invoke_work_stealing((), 3);
+        assert_eq!(stats.called.len(), 3);
+        assert_eq!(stats.called.iter().sum::<usize>(), calls.len());
+        assert!(stats.failures.is_empty());
+        assert!(calls.iter().all(|count| count.load(Ordering::SeqCst) == 1));
+    }
+
+    #[test]
+    fn work_stealing_collects_panics() {
+        let event = Event::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        let failing = event.add_fn(|_: ()| panic!("boom")).unwrap();
+        let _handles: Vec<_> = (0..4)
+            .map(|_| {
+                let count = count.clone();
+                event.add_fn(move |_: ()| {
+                    count.fetch_add(1, Ordering::SeqCst);
+                })
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let stats = event.invoke_work_stealing((), 2);
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+        assert_eq!(stats.called.iter().sum::<usize>(), 4);
+        assert_eq!(
+            stats.failures,
+            vec![HandlerFailure {
+                id: failing.id(),
+                message: String::from("boom"),
+            }]
+        );
+    }
+
+    #[test]
+    fn flagged_handlers_follow_their_flag() {
+        let flags = Arc::new(RwLock::new(HashMap::new()));
+        let event = Event::with_flags(flags.clone());
+        let count = Arc::new(AtomicUsize::new(0));
+        let _handle = {
+            let count = count.clone();
+            event.add_fn_flagged("beta", move |_: ()| {
+                count.fetch_add(1, Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+
+        flags.write().unwrap().insert("beta".to_string(), false);
+        event.invoke(());
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        flags.write().unwrap().insert("beta".to_string(), true);
+        event.invoke(());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn pumping_runs_queued_calls_by_priority() {
+        let event = Event::queued();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let handles: Vec<_> = [2, 0, 1]
+            .into_iter()
+            .map(|priority| {
+                let calls = calls.clone();
+                event.add_fn_with_priority(
+                    move |_: ()| calls.lock().unwrap().push(priority),
+                    priority,
+                )
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        event.invoke(());
+        assert!(calls.lock().unwrap().is_empty());
+        assert_eq!(event.pending(), handles.len());
+
+        assert_eq!(event.pump(2), 2);
+        assert_eq!(*calls.lock().unwrap(), vec![0, 1]);
+        assert_eq!(event.pump(2), 1);
+        assert_eq!(event.pump(2), 0);
+        assert_eq!(*calls.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn invoke_first_n_fires_only_the_leading_handlers() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let handles: Vec<_> = (0..5)
+            .map(|index| {
+                let calls = calls.clone();
+                event.add_fn(move |_: ()| calls.lock().unwrap().push(index))
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        event.invoke(());
+        let order = std::mem::take(&mut *calls.lock().unwrap());
+        assert_eq!(order.len(), handles.len());
+
+        assert_eq!(event.invoke_first_n((), 2), 2);
+        assert_eq!(*calls.lock().unwrap(), order[..2]);
+    }
+
+    #[test]
+    fn invoke_first_n_dispatches_like_invoke() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| calls.lock().unwrap().push((name, value))
+        };
+        let _late = event.add_fn(recorder("late")).unwrap();
+        let _early = event.add_fn_with_priority(recorder("early"), -1).unwrap();
+        event.set_validator(|value: &i32| *value > 0);
+
+        assert_eq!(event.invoke_first_n(0, 1), 0);
+        assert!(calls.lock().unwrap().is_empty());
+
+        assert_eq!(event.invoke_first_n(1, 1), 1);
+        assert_eq!(*calls.lock().unwrap(), vec![("early", 1)]);
+    }
+
+    #[test]
+    fn invoke_safe_checked_reflects_unwinding_support() {
+        let event = Event::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let _handle = {
+            let calls = calls.clone();
+            event.add_fn(move |_: ()| {
+                calls.fetch_add(1, Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+
+        let result = event.invoke_safe_checked(());
+        assert_eq!(
+            Event::<()>::panic_isolation_available(),
+            cfg!(panic = "unwind")
+        );
+        assert_eq!(result.is_ok(), Event::<()>::panic_isolation_available());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[cfg(feature = "futures")]
+    #[tokio::test]
+    async fn cancelled_invocation_aborts_pending_handlers() {
+        let event = Event::new();
+        let completed = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..3)
+            .map(|_| {
+                let completed = completed.clone();
+                event.add_async_cancellable(move |_: ()| {
+                    let completed = completed.clone();
+                    async move {
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        completed.fetch_add(1, Ordering::SeqCst);
+                    }
+                })
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        // Fires while the second handler is running.
+        let token = CancellationToken::new();
+        tokio::spawn({
+            let token = token.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(75)).await;
+                token.cancel();
+            }
+        });
+
+        assert_eq!(event.invoke_async_cancellable((), token).await, 1);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(completed.load(Ordering::SeqCst), 1);
+        assert_eq!(event.len(), handles.len());
+    }
+
+    #[test]
+    fn validator_rejects_invalid_arguments() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let calls = calls.clone();
+            event.add_fn(move |value: i32| calls.lock().unwrap().push(value))
+        }
+        .unwrap();
+        event.set_validator(|value| *value >= 0);
+
+        assert_eq!(event.try_invoke(-1), Err(EventInvocationError::InvalidArgs));
+        event.invoke(-2);
+        assert!(calls.lock().unwrap().is_empty());
+
+        assert_eq!(event.try_invoke(1), Ok(()));
+        assert_eq!(*calls.lock().unwrap(), vec![1]);
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn par_timeout_calls_handlers_inline_if_no_thread_can_be_spawned() {
+        let event = Event::new();
+        let count = Arc::new(AtomicUsize::new(0));
+        let _handle = {
+            let count = count.clone();
+            event.add_fn(move |_: ()| {
+                count.fetch_add(1, Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+
+        // No thread can reserve a stack this large.
+        event.set_handler_stack_size(1 << 60);
+        let pending = event.invoke_par_timeout((), Duration::from_secs(1));
+        assert!(pending.is_empty());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn handler_threads_use_configured_stack_size() {
+        fn recurse(depth: usize) -> u8 {
+            let frame = std::hint::black_box([depth as u8; 4096]);
+            match depth {
+                0 => frame[0],
+                _ => frame[depth % 4096].wrapping_add(recurse(depth - 1)),
+            }
+        }
+
+        let event = Event::new();
+        let done = Arc::new(AtomicBool::new(false));
+        let _handle = {
+            let done = done.clone();
+            event.add_fn(move |depth: usize| {
+                std::hint::black_box(recurse(depth));
+                done.store(true, Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+
+        // About 8 MiB of stack, well above the default of spawned threads.
+        event.set_handler_stack_size(64 * 1024 * 1024);
+        assert_eq!(event.invoke_work_stealing(2048, 1).called, vec![1]);
+        assert!(done.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn lifecycle_callbacks_observe_registrations() {
+        let event = Event::new();
+        let subscribed = Arc::new(Mutex::new(Vec::new()));
+        let unsubscribed = Arc::new(Mutex::new(Vec::new()));
+        event.set_on_subscribe({
+            let subscribed = subscribed.clone();
+            move |id| subscribed.lock().unwrap().push(id)
+        });
+        event.set_on_unsubscribe({
+            let unsubscribed = unsubscribed.clone();
+            move |id| unsubscribed.lock().unwrap().push(id)
+        });
+
+        let handle = event.add_fn(|_: ()| {}).unwrap();
+        let id = handle.id();
+        assert_eq!(*subscribed.lock().unwrap(), vec![id]);
+        assert!(unsubscribed.lock().unwrap().is_empty());
+
+        drop(handle);
+        assert_eq!(*unsubscribed.lock().unwrap(), vec![id]);
+    }
+
+    #[test]
+    fn rotating_start_rotates_the_first_handler() {
+        let event = Event::new();
+        event.set_order_policy(OrderPolicy::RotatingStart);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let _handles: Vec<_> = ["A", "B", "C"]
+            .into_iter()
+            .map(|name| {
+                let calls = calls.clone();
+                event.add_fn(move |_: ()| calls.lock().unwrap().push(name))
+            })
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        for expected in [["A", "B", "C"], ["B", "C", "A"], ["C", "A", "B"]] {
+            event.invoke(());
+            assert_eq!(std::mem::take(&mut *calls.lock().unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn invoke_with_stats_reports_the_taken_path() {
+        let event = Event::new();
+        let stats = event.invoke_with_stats(());
+        assert!(stats.short_circuited);
+        assert_eq!(stats.fired, 0);
+        assert_eq!(stats.cloned_args, 0);
+
+        let _a = event.add_fn(|_: ()| {}).unwrap();
+        let _b = event.add_fn(|_: ()| {}).unwrap();
+        let stats = event.invoke_with_stats(());
+        assert!(!stats.short_circuited);
+        assert_eq!(stats.fired, 2);
+        assert_eq!(stats.cloned_args, 2);
+
+        event.set_enabled(false);
+        let stats = event.invoke_with_stats(());
+        assert!(stats.short_circuited);
+        assert_eq!(stats.fired, 0);
+    }
+
+    #[test]
+    fn invoke_with_stats_follows_the_reentrancy_policy() {
+        let event = Arc::new(Event::new());
+        event.set_reentrancy(Reentrancy::Queue);
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let nested = Arc::new(Mutex::new(None));
+        let _handle = {
+            let (weak, calls, nested) = (Arc::downgrade(&event), calls.clone(), nested.clone());
+            event.add_fn(move |depth: u32| {
+                calls.lock().unwrap().push(depth);
+                if depth == 0 {
+                    let stats = weak.upgrade().unwrap().invoke_with_stats(1);
+                    *nested.lock().unwrap() = Some(stats);
+                }
+            })
+        }
+        .unwrap();
+
+        let stats = event.invoke_with_stats(0);
+        assert_eq!(stats.fired, 1);
+        assert!(nested.lock().unwrap().unwrap().short_circuited);
+        assert_eq!(*calls.lock().unwrap(), vec![0, 1]);
+    }
+
+    #[test]
+    fn add_handler_error_keeps_its_message() {
+        assert_eq!(
+            AddHandlerError::AlreadyRegistered.to_string(),
+            "The handler was already registered"
+        );
+    }
+
+    #[test]
+    fn add_fn_every_fires_on_every_nth_invocation() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let calls = calls.clone();
+            event.add_fn_every(2, move |invocation: i32| {
+                calls.lock().unwrap().push(invocation)
+            })
+        }
+        .unwrap();
+
+        for invocation in 1..=4 {
+            event.invoke(invocation);
+        }
+        assert_eq!(*calls.lock().unwrap(), vec![2, 4]);
+    }
+
+    #[test]
+    fn invocation_count_wraps_around() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |invocation: i32| calls.lock().unwrap().push((name, invocation))
+        };
+        let _every = event.add_fn_every(2, recorder("every")).unwrap();
+        let _always = event.add_fn(recorder("always")).unwrap();
+
+        event
+            .handlers
+            .invocations
+            .store(u64::MAX - 1, Ordering::Relaxed);
+        // Counted as invocations `u64::MAX`, `0` and `1`.
+        for invocation in 1..=3 {
+            event.invoke(invocation);
+        }
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![("always", 1), ("every", 2), ("always", 2), ("always", 3)]
+        );
+
+        calls.lock().unwrap().clear();
+        event.reset_sequence();
+        for invocation in 1..=2 {
+            event.invoke(invocation);
+        }
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![("always", 1), ("every", 2), ("always", 2)]
+        );
+    }
+
+    #[test]
+    fn unhandled_routed_invocations_escalate() {
+        let event = Event::new();
+        let unhandled = Arc::new(Mutex::new(Vec::new()));
+        event.set_on_unhandled({
+            let unhandled = unhandled.clone();
+            move |value: &i32| unhandled.lock().unwrap().push(*value)
+        });
+
+        let _a = event.add_fn_with_context(|_, _: i32| {}).unwrap();
+        let _b = event.add_fn_with_context(|_, _: i32| {}).unwrap();
+        assert!(!event.invoke_routed(7));
+        assert_eq!(*unhandled.lock().unwrap(), vec![7]);
+
+        let _handling = event
+            .add_fn_with_context(|context, value: i32| {
+                if value > 5 {
+                    context.set_handled();
+                }
+            })
+            .unwrap();
+        assert!(event.invoke_routed(8));
+        assert_eq!(*unhandled.lock().unwrap(), vec![7]);
+    }
+
+    #[test]
+    fn handler_can_drop_a_sibling_handle_during_invoke() {
+        let event = Event::new();
+        let sibling: Arc<Mutex<Option<EventHandle<()>>>> = Arc::new(Mutex::new(None));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let _dropping = {
+            let sibling = sibling.clone();
+            event.add_fn(move |_: ()| drop(sibling.lock().unwrap().take()))
+        }
+        .unwrap();
+        *sibling.lock().unwrap() = Some({
+            let calls = calls.clone();
+            event
+                .add_fn(move |_: ()| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                })
+                .unwrap()
+        });
+        assert_eq!(event.len(), 2);
+
+        event.invoke(());
+        assert_eq!(event.len(), 1);
+
+        let called = calls.load(Ordering::SeqCst);
+        event.invoke(());
+        assert_eq!(calls.load(Ordering::SeqCst), called);
+    }
+
+    #[test]
+    fn handler_registered_during_invoke_fires_on_the_next_invoke() {
+        let event = Arc::new(Event::new());
+        let added: Arc<Mutex<Vec<EventHandle<()>>>> = Arc::new(Mutex::new(Vec::new()));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let _registering = {
+            let weak = Arc::downgrade(&event);
+            let added = added.clone();
+            let calls = calls.clone();
+            event.add_fn(move |_: ()| {
+                let mut added = added.lock().unwrap();
+                if added.is_empty() {
+                    let calls = calls.clone();
+                    let event = weak.upgrade().unwrap();
+                    added.push(
+                        event
+                            .add_fn(move |_: ()| {
+                                calls.fetch_add(1, Ordering::SeqCst);
+                            })
+                            .unwrap(),
+                    );
+                }
+            })
+        }
+        .unwrap();
+
+        event.invoke(());
+        assert_eq!(event.len(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        event.invoke(());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn dedup_window_suppresses_repeated_args() {
+        let event = Event::new();
+        let _handle = event.add_fn(|_: i32| {}).unwrap();
+        event.set_dedup_window(Duration::from_millis(50));
+
+        assert_eq!(event.invoke_with_stats(5).fired, 1);
+        assert_eq!(event.invoke_with_stats(5).fired, 0);
+        assert_eq!(event.invoke_with_stats(6).fired, 1);
+
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(event.invoke_with_stats(5).fired, 1);
+    }
+
+    #[test]
+    fn unregister_removes_a_live_handler() {
+        let event = Event::new();
+        let handle = event.add_fn(|_: ()| {}).unwrap();
+        assert_eq!(event.len(), 1);
+
+        assert!(handle.unregister());
+        assert_eq!(event.len(), 0);
+    }
+
+    #[test]
+    fn unregister_after_the_event_was_dropped() {
+        let event = Event::new();
+        let handle = event.add_fn(|_: ()| {}).unwrap();
+        drop(event);
+
+        assert!(!handle.unregister());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn unregister_of_a_removed_handler() {
+        let event = Event::new();
+        let handle = event.add_fnonce(|_: ()| {}).unwrap();
+        let _other = event.add_fn(|_: ()| {}).unwrap();
+        event.invoke(());
+
+        assert!(!handle.unregister());
+        assert_eq!(event.len(), 1);
+    }
+
+    #[test]
+    fn forwarding_with_priority_runs_after_own_handlers() {
+        let source = Event::new();
+        let target = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |_: ()| calls.lock().unwrap().push(name)
+        };
+
+        let _forward = source.forward_to_with_priority(&target, 10).unwrap();
+        let _first = source.add_fn(recorder("first")).unwrap();
+        let _second = source.add_fn(recorder("second")).unwrap();
+        let _target = target.add_fn(recorder("target")).unwrap();
+
+        source.invoke(());
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[2], "target");
+    }
+
+    #[test]
+    fn forgotten_handle_keeps_the_handler() {
+        let event = Event::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        {
+            let calls = calls.clone();
+            event
+                .add_fn(move |_: ()| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                })
+                .unwrap()
+                .forget();
+        }
+        assert_eq!(event.len(), 1);
+
+        event.invoke(());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn fnmut_handler_mutates_its_state() {
+        let event = Event::new();
+        let total = Arc::new(AtomicUsize::new(0));
+        let _handle = {
+            let total = total.clone();
+            let mut count = 0;
+            event.add_fnmut(move |amount: usize| {
+                count += amount;
+                total.store(count, Ordering::SeqCst);
+            })
+        }
+        .unwrap();
+
+        for amount in 1..=3 {
+            event.invoke(amount);
+        }
+        assert_eq!(total.load(Ordering::SeqCst), 6);
+    }
+
+    #[test]
+    fn fnmut_handler_is_skipped_by_nested_invocations() {
+        let event = Arc::new(Event::new());
+        let nested = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let (weak, nested) = (Arc::downgrade(&event), nested.clone());
+            event.add_fnmut(move |depth: u32| {
+                if depth < 2 {
+                    let fired = weak.upgrade().unwrap().invoke(depth + 1);
+                    nested.lock().unwrap().push(fired);
+                }
+            })
+        }
+        .unwrap();
+        let _other = event.add_fn(|_: u32| ()).unwrap();
+
+        assert_eq!(event.invoke(0), 2);
+        assert_eq!(*nested.lock().unwrap(), vec![1]);
+        assert_eq!(event.invoke(0), 2);
+    }
+
+    #[test]
+    fn order_comparator_sorts_by_tag_descending() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let _handles: Vec<_> = [2, 7, 4]
+            .into_iter()
+            .map(|tag| {
+                let calls = calls.clone();
+                event
+                    .add_fn_tagged(tag, move |_: ()| calls.lock().unwrap().push(tag))
+                    .unwrap()
+            })
+            .collect();
+        event.set_order_comparator(|a, b| b.tag.cmp(&a.tag));
+
+        event.invoke(());
+        assert_eq!(*calls.lock().unwrap(), vec![7, 4, 2]);
+    }
+
+    #[test]
+    fn invoke_ref_fans_out_non_clone_args() {
+        struct Payload(Vec<u8>);
+
+        let event = Event::new();
+        let total = Arc::new(AtomicUsize::new(0));
+        let _handles: Vec<_> = (1..=3)
+            .map(|factor| {
+                let total = total.clone();
+                event
+                    .add_fn_ref(move |payload: &Payload| {
+                        total.fetch_add(factor * payload.0.len(), Ordering::SeqCst);
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        event.invoke_ref(&Payload(vec![0; 4]));
+        assert_eq!(total.load(Ordering::SeqCst), 24);
+    }
+
+    #[test]
+    fn invoke_calls_ref_handlers() {
+        let event = Event::new();
+        let seen = Arc::new(AtomicUsize::new(0));
+        let _handle = {
+            let seen = seen.clone();
+            event.add_fn_ref(move |value: &usize| seen.store(*value, Ordering::SeqCst))
+        }
+        .unwrap();
+
+        event.invoke(5);
+        assert_eq!(seen.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn invoke_and_wait_blocks_until_scheduled_calls_completed() {
+        struct Spawning;
+        impl Scheduler for Spawning {
+            fn schedule(&self, task: Box<dyn FnOnce() + Send>) {
+                thread::spawn(move || {
+                    thread::sleep(Duration::from_millis(20));
+                    task()
+                });
+            }
+        }
+
+        let event = Event::with_scheduler(Spawning);
+        let count = Arc::new(AtomicUsize::new(0));
+        let _handles: Vec<_> = (0..3)
+            .map(|_| {
+                let count = count.clone();
+                event
+                    .add_fn(move |_: ()| {
+                        count.fetch_add(1, Ordering::SeqCst);
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        event.invoke_and_wait(());
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn clear_removes_all_handlers() {
+        let event = Event::new();
+        let first = event.add_fn(|_: ()| {}).unwrap();
+        let _second = event.add_fn(|_: ()| {}).unwrap();
+
+        assert_eq!(event.clear(), 2);
+        assert_eq!(event.len(), 0);
+        assert_eq!(event.clear(), 0);
+
+        assert_eq!(first.invoke(()), Err(EventInvocationError::NoHandlers));
+        assert!(!first.unregister());
+    }
+
+    #[test]
+    fn remove_unregisters_by_id() {
+        let event = Event::new();
+        let count = Arc::new(Mutex::new(0));
+        let handle = {
+            let count = count.clone();
+            event.add_fn(move |_: ()| *count.lock().unwrap() += 1)
+        }
+        .unwrap();
+        let id: HandlerId = handle.id();
+
+        assert!(event.remove(id));
+        assert!(!event.remove(id));
+        assert_eq!(event.len(), 0);
+
+        assert_eq!(handle.invoke(()), Err(EventInvocationError::NoHandlers));
+        assert_eq!(*count.lock().unwrap(), 0);
+        assert!(!handle.unregister());
+    }
+
+    #[test]
+    fn stale_handles_keep_unique_reregistrations() {
+        let event = Event::new();
+
+        let removed = event.add_ptr_unique(dummy).unwrap();
+        assert!(event.remove(removed.id()));
+        let readded = event.add_ptr_unique(dummy).unwrap();
+        assert_ne!(removed.id(), readded.id());
+        drop(removed);
+        assert_eq!(event.len(), 1);
+
+        let cleared = event.add_fn_unique(1, |_: ()| ()).unwrap();
+        event.clear();
+        let _readded = event.add_fn_unique(1, |_: ()| ()).unwrap();
+        drop((cleared, readded));
+        assert_eq!(event.len(), 1);
+
+        let retained = event.add_ptr_unique(dummy).unwrap();
+        event.retain(|_, kind| *kind != HandlerKind::Function);
+        let _readded = event.add_ptr_unique(dummy).unwrap();
+        drop(retained);
+        assert_eq!(event.len(), 2);
+    }
+
+    #[test]
+    fn retain_removes_rejected_handlers() {
+        let event = Event::new();
+        let unsubscribed = Arc::new(Mutex::new(Vec::new()));
+        event.set_on_unsubscribe({
+            let unsubscribed = unsubscribed.clone();
+            move |id| unsubscribed.lock().unwrap().push(id)
+        });
+
+        let captured = Arc::new(());
+        let closure = {
+            let captured = captured.clone();
+            event.add_fn(move |_: ()| drop(captured.clone()))
+        }
+        .unwrap();
+        let mutable = {
+            let captured = captured.clone();
+            event.add_fnmut(move |_: ()| drop(captured.clone()))
+        }
+        .unwrap();
+        let _function = event.add_ptr(dummy).unwrap();
+
+        let removed = event.retain(|_, kind| *kind == HandlerKind::Function);
+        assert_eq!(removed, 2);
+        assert_eq!(Arc::strong_count(&captured), 1);
+        assert_eq!(
+            *unsubscribed.lock().unwrap(),
+            vec![closure.id(), mutable.id()]
+        );
+
+        assert_eq!(event.retain(|_, _| true), 0);
+        assert_eq!(event.invoke(()), 1);
+    }
+
+    #[test]
+    fn handlers_registered_after_clear_survive_old_handles() {
+        let event = Event::new();
+        let stale = event.add_fn(|_: ()| {}).unwrap();
+        event.clear();
+
+        let _fresh = event.add_fn(|_: ()| {}).unwrap();
+        drop(stale);
+        assert_eq!(event.len(), 1);
+    }
+
+    #[test]
+    fn invoke_try_all_aggregates_handler_errors() {
+        let event = Event::new();
+        let failing = |error: &'static str| move |_: i32| Err(error);
+        let _succeeding = event.add_try_fn(|_: i32| Ok::<(), &str>(())).unwrap();
+        let first = event.add_try_fn(failing("first")).unwrap();
+        let second = event.add_try_fn(failing("second")).unwrap();
+
+        let mut errors = event.invoke_try_all::<&str>(1).unwrap_err().into_errors();
+        errors.sort();
+        let mut expected = vec![(first.id(), "first"), (second.id(), "second")];
+        expected.sort();
+        assert_eq!(errors, expected);
+
+        drop((first, second));
+        assert_eq!(event.invoke_try_all::<&str>(1), Ok(()));
+    }
+
+    #[test]
+    fn accumulators_are_not_shared_with_other_invocations() {
+        let inner = Arc::new(Event::new());
+        let _inner_error = inner.add_try_fn(|_: i32| Err("inner")).unwrap();
+        let _inner_break = inner.add_fn_flow(|_: i32| ControlFlow::Break(())).unwrap();
+
+        let outer = Arc::new(Event::new());
+        let forwarding = {
+            let (inner, weak) = (inner.clone(), Arc::downgrade(&outer));
+            outer.add_try_fn(move |nested: bool| {
+                inner.invoke(1);
+                if !nested {
+                    weak.upgrade().unwrap().invoke(true);
+                }
+                Err("outer")
+            })
+        }
+        .unwrap();
+
+        let errors = outer.invoke_try_all::<&str>(false).unwrap_err();
+        assert_eq!(errors.into_errors(), vec![(forwarding.id(), "outer")]);
+        assert!(!outer.invoke_until(false));
+    }
+
+    #[test]
+    fn invoke_until_stops_once_a_handler_breaks() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str, flow: ControlFlow<()>| {
+            let calls = calls.clone();
+            move |_: ()| {
+                calls.lock().unwrap().push(name);
+                flow
+            }
+        };
+        let _continuing = event
+            .add_fn_flow(recorder("continuing", ControlFlow::Continue(())))
+            .unwrap();
+        let breaking = event
+            .add_fn_flow(recorder("breaking", ControlFlow::Break(())))
+            .unwrap();
+        let _later = event
+            .add_fn_flow(recorder("later", ControlFlow::Continue(())))
+            .unwrap();
+
+        assert!(event.invoke_until(()));
+        assert_eq!(*calls.lock().unwrap(), vec!["continuing", "breaking"]);
+
+        calls.lock().unwrap().clear();
+        event.invoke(());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["continuing", "breaking", "later"]
+        );
+
+        calls.lock().unwrap().clear();
+        let _first = event
+            .add_fn_with_priority(
+                {
+                    let calls = calls.clone();
+                    move |_: ()| calls.lock().unwrap().push("first")
+                },
+                -1,
+            )
+            .unwrap();
+        drop(breaking);
+        assert!(!event.invoke_until(()));
+        assert_eq!(*calls.lock().unwrap(), vec!["first", "continuing", "later"]);
+    }
+
+    #[test]
+    fn try_invoke_first_err_stops_at_the_first_failure() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str, fails: bool| {
+            let calls = calls.clone();
+            move |_: ()| {
+                calls.lock().unwrap().push(name);
+                if fails {
+                    Err(name)
+                } else {
+                    Ok(())
+                }
+            }
+        };
+        let _succeeding = event.add_try_fn(recorder("succeeding", false)).unwrap();
+        let first = event.add_try_fn(recorder("first", true)).unwrap();
+        let _second = event.add_try_fn(recorder("second", true)).unwrap();
+
+        assert_eq!(event.try_invoke_first_err::<&str>(()), Err("first"));
+        assert_eq!(*calls.lock().unwrap(), vec!["succeeding", "first"]);
+
+        calls.lock().unwrap().clear();
+        drop(first);
+        assert_eq!(event.try_invoke_first_err::<&str>(()), Err("second"));
+        assert_eq!(*calls.lock().unwrap(), vec!["succeeding", "second"]);
+    }
+
+    #[test]
+    fn clones_share_handlers() {
+        let event = Event::new();
+        let clone = event.clone();
+        let count = Arc::new(Mutex::new(0));
+        let handle = {
+            let count = count.clone();
+            event.add_fn(move |_: ()| *count.lock().unwrap() += 1)
+        }
+        .unwrap();
+
+        assert_eq!(clone.invoke(()), 1);
+        assert_eq!(*count.lock().unwrap(), 1);
+
+        drop(event);
+        assert!(handle.is_valid());
+        assert_eq!(clone.invoke(()), 1);
+        assert_eq!(*count.lock().unwrap(), 2);
+
+        drop(clone);
+        assert!(!handle.is_valid());
+    }
+
+    #[test]
+    fn debug_formats_handler_counts() {
+        let event = Event::new();
+        assert_eq!(format!("{event:?}"), "Event { handlers: 0 }");
+
+        let _first = event.add_fn(|_: ()| {}).unwrap();
+        let _second = event.add_fn(|_: ()| {}).unwrap();
+        let _mutable = event.add_fnmut(|_: ()| {}).unwrap();
+        let handle = event.add_ptr(dummy).unwrap();
+        assert_eq!(
+            format!("{event:?}"),
+            "Event { handlers: 4, fn: 2, fn_mut: 1, ptr: 1 }"
+        );
+
+        assert!(format!("{handle:?}").contains("valid: true"));
+        drop(event);
+        assert!(format!("{handle:?}").contains("valid: false"));
+    }
+
+    #[test]
+    fn is_empty_reflects_registrations() {
+        let event = Event::new();
+        assert!(event.is_empty());
+
+        let handle = event.add_fn(|_: ()| {}).unwrap();
+        assert!(!event.is_empty());
+        assert!(!handle.is_empty());
+
+        drop(event);
+        assert!(handle.is_empty());
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn invoke_counts_called_handlers() {
+        let event = Event::new();
+        let _fn = event.add_fn(|_: ()| {}).unwrap();
+        let _once = event.add_fnonce(|_: ()| {}).unwrap();
+        assert_eq!(event.invoke(()), 2);
+        assert_eq!(event.invoke(()), 1);
+
+        let handle = event.add_fnonce(|_: ()| {}).unwrap();
+        assert_eq!(Invoke::invoke(&handle, ()), 2);
+        assert_eq!(Invoke::invoke(&handle, ()), 1);
+        drop(event);
+        assert_eq!(Invoke::invoke(&handle, ()), 0);
+    }
+
+    #[test]
+    fn variant_handlers_only_fire_for_their_variant() {
+        #[derive(Clone)]
+        enum Message {
+            Number(usize),
+            Text(&'static str),
+        }
+
+        let event = Event::new();
+        let numbers = Arc::new(AtomicUsize::new(0));
+        let texts = Arc::new(Mutex::new(Vec::new()));
+        let _numbers = {
+            let numbers = numbers.clone();
+            event.add_fn_variant(
+                |message| match message {
+                    Message::Number(number) => Some(number),
+                    Message::Text(_) => None,
+                },
+                move |number| {
+                    numbers.fetch_add(number, Ordering::SeqCst);
+                },
+            )
+        }
+        .unwrap();
+        let _texts = {
+            let texts = texts.clone();
+            event.add_fn_variant(
+                |message| match message {
+                    Message::Text(text) => Some(text),
+                    Message::Number(_) => None,
+                },
+                move |text| texts.lock().unwrap().push(text),
+            )
+        }
+        .unwrap();
+
+        event.invoke(Message::Number(3));
+        event.invoke(Message::Text("hello"));
+        event.invoke(Message::Number(4));
+
+        assert_eq!(numbers.load(Ordering::SeqCst), 7);
+        assert_eq!(*texts.lock().unwrap(), vec!["hello"]);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn disabled_event_calls_no_handlers() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| calls.lock().unwrap().push((name, value))
+        };
+        let _fn = event.add_fn(recorder("fn")).unwrap();
+        let _once = event.add_fnonce(recorder("once")).unwrap();
+        assert!(event.is_enabled());
+
+        event.set_enabled(false);
+        assert!(!event.is_enabled());
+        assert_eq!(event.invoke(1), 0);
+        assert_eq!(event.try_invoke(2), Ok(()));
+        assert!(event.invoke_catching(3).is_empty());
+        assert_eq!(event.len(), 2);
+
+        event.set_enabled(true);
+        assert_eq!(event.invoke(4), 2);
+        assert_eq!(event.invoke(5), 1);
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![("fn", 4), ("once", 4), ("fn", 5)]
+        );
+    }
+
+    #[test]
+    fn blocked_handlers_are_skipped() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| calls.lock().unwrap().push((name, value))
+        };
+        let first = event.add_fn(recorder("first")).unwrap();
+        let second = event.add_fn(recorder("second")).unwrap();
+
+        assert!(first.set_blocked(true));
+        assert_eq!(event.invoke(1), 1);
+        assert_eq!(event.len(), 2);
+
+        assert!(event.set_blocked(first.id(), false));
+        assert!(event.set_blocked(second.id(), true));
+        assert_eq!(event.invoke(2), 1);
+        assert_eq!(*calls.lock().unwrap(), vec![("second", 1), ("first", 2)]);
+
+        let id = second.id();
+        drop(second);
+        assert!(!event.set_blocked(id, false));
+    }
+
+    #[test]
+    fn blocked_handlers_are_skipped_by_every_invocation_path() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| calls.lock().unwrap().push((name, value))
+        };
+        let _active = event.add_fn(recorder("active")).unwrap();
+        let blocked = event.add_fn(recorder("blocked")).unwrap();
+        blocked.set_blocked(true);
+
+        event.invoke(1);
+        event.invoke_timed(2);
+        event.invoke_catching(3);
+        event.invoke_panic_safe(4);
+        event.invoke_par_timeout(5, Duration::from_secs(1));
+        event.invoke_work_stealing(6, 2);
+        event.invoke_first_n(7, 2);
+
+        let mut calls = calls.lock().unwrap().clone();
+        calls.sort();
+        let expected: Vec<_> = (1..=7).map(|value| ("active", value)).collect();
+        assert_eq!(calls, expected);
+    }
+
+    #[test]
+    fn invoke_catching_continues_after_a_panic() {
+        let event = Event::new();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = |priority: i32| {
+            let calls = calls.clone();
+            event
+                .add_fn_with_priority(
+                    move |_: ()| {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                    },
+                    priority,
+                )
+                .unwrap()
+        };
+        let _first = counter(-1);
+        let panicking = event.add_fn(|_: ()| panic!("handler failed")).unwrap();
+        let _last = counter(1);
+
+        assert_eq!(event.invoke_catching(()), vec![panicking.id()]);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        assert_eq!(event.invoke_catching(()), vec![panicking.id()]);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    #[cfg(not(feature = "no_fnonce"))]
+    fn consumed_one_shot_handlers_are_dropped_in_firing_order() {
+        struct Resource(usize, Arc<Mutex<Vec<usize>>>);
+        impl Drop for Resource {
+            fn drop(&mut self) {
+                self.1.lock().unwrap().push(self.0);
+            }
+        }
+
+        let event = Event::new();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+        let _handles: Vec<_> = (0..2)
+            .map(|index| {
+                let resource = Resource(index, dropped.clone());
+                let fired = fired.clone();
+                event
+                    .add_fnonce(move |_: ()| {
+                        let resource = resource;
+                        fired.lock().unwrap().push(resource.0);
+                    })
+                    .unwrap()
+            })
+            .collect();
+
+        assert!(dropped.lock().unwrap().is_empty());
+        assert_eq!(event.invoke(()), 2);
+        assert_eq!(dropped.lock().unwrap().len(), 2);
+        assert_eq!(*dropped.lock().unwrap(), *fired.lock().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "no_fnonce")]
+    fn invoke_takes_no_write_lock_without_one_shot_handlers() {
+        let event = Event::new();
+        let _handle = event.add_fn(|_: ()| {}).unwrap();
+
+        let _read = event.handlers.read().unwrap();
+        assert!(event.handlers.try_write().is_err());
+        assert_eq!(event.invoke(()), 1);
+    }
+
+    #[test]
+    fn invoke_calls_handlers_by_priority_then_registration() {
+        let event = Event::new();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let _handles: Vec<_> = [(5, "last"), (0, "second"), (-1, "first"), (0, "third")]
+            .into_iter()
+            .map(|(priority, name)| {
+                let calls = calls.clone();
+                event
+                    .add_fn_with_priority(move |_: ()| calls.lock().unwrap().push(name), priority)
+                    .unwrap()
+            })
+            .collect();
+
+        for _ in 0..2 {
+            calls.lock().unwrap().clear();
+            event.invoke(());
+            assert_eq!(
+                *calls.lock().unwrap(),
+                vec!["first", "second", "third", "last"]
+            );
+        }
     }
 
     #[test]
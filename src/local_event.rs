@@ -0,0 +1,171 @@
+use crate::{HandleKey, RegistrationId};
+use std::cell::{Cell, RefCell, RefMut};
+use std::collections::BTreeMap;
+
+/// The handlers of a [`LocalEvent`].
+type Handlers<TEventArgs> = BTreeMap<RegistrationId, Box<dyn Fn(TEventArgs)>>;
+
+/// An event for single-threaded use.
+///
+/// Handlers need not be `Send`, and the handler map is guarded by a [`RefCell`]
+/// instead of a lock, so registering and invoking involve no atomic operations.
+/// The event itself is neither `Send` nor `Sync`.
+///
+/// Handlers may invoke the event again, but registering or removing handlers
+/// while the event is being invoked panics.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::LocalEvent;
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// let event = LocalEvent::new();
+/// let total = Rc::new(Cell::new(0));
+/// let id = event.add_fn({
+///     let total = total.clone();
+///     move |amount: i32| total.set(total.get() + amount)
+/// });
+///
+/// event.invoke(21);
+/// event.invoke(21);
+/// assert_eq!(total.get(), 42);
+/// assert!(event.remove(id));
+/// ```
+pub struct LocalEvent<TEventArgs> {
+    handlers: RefCell<Handlers<TEventArgs>>,
+    /// The identifier of the next registration.
+    next_id: Cell<u64>,
+}
+
+impl<TEventArgs> LocalEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            handlers: RefCell::new(BTreeMap::new()),
+            next_id: Cell::new(0),
+        }
+    }
+
+    /// Registers a closure.
+    ///
+    /// ## Returns
+    /// The identifier to pass to [`LocalEvent::remove`]; it is only meaningful
+    /// to the event that issued it.
+    ///
+    /// ## Panics
+    /// Panics if called by a handler of this event.
+    pub fn add_fn<T>(&self, handler: T) -> RegistrationId
+    where
+        T: Fn(TEventArgs) + 'static,
+    {
+        let handler = Box::new(handler);
+        let id = RegistrationId(HandleKey::Id(self.next_id.get()));
+        self.next_id.set(self.next_id.get() + 1);
+        self.handlers_mut().insert(id, handler);
+        id
+    }
+
+    /// Unregisters the specified handler.
+    ///
+    /// Returns whether the handler was registered.
+    ///
+    /// ## Panics
+    /// Panics if called by a handler of this event.
+    pub fn remove(&self, id: RegistrationId) -> bool {
+        self.handlers_mut().remove(&id).is_some()
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.borrow().len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Invokes the event.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The number of handlers called.
+    pub fn invoke(&self, args: TEventArgs) -> usize {
+        let handlers = self.handlers.borrow();
+        for handler in handlers.values() {
+            handler(args.clone());
+        }
+        handlers.len()
+    }
+
+    /// Borrows the handler map for modification.
+    fn handlers_mut(&self) -> RefMut<'_, Handlers<TEventArgs>> {
+        self.handlers
+            .try_borrow_mut()
+            .expect("Handlers of a LocalEvent cannot be added or removed while it is being invoked")
+    }
+}
+
+impl<TEventArgs> Default for LocalEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    #[should_panic(expected = "cannot be added or removed while it is being invoked")]
+    fn registering_during_invoke_panics() {
+        let event = Rc::new(LocalEvent::new());
+        let weak = Rc::downgrade(&event);
+        event.add_fn(move |_: ()| {
+            weak.upgrade().unwrap().add_fn(|_: ()| {});
+        });
+
+        event.invoke(());
+    }
+
+    #[test]
+    fn invoke_counts_called_handlers() {
+        let event = LocalEvent::new();
+        let first = event.add_fn(|_: ()| {});
+        let second = event.add_fn(|_: ()| {});
+        assert_ne!(first, second);
+        assert_eq!(event.invoke(()), 2);
+
+        assert!(event.remove(first));
+        assert_eq!(event.invoke(()), 1);
+    }
+
+    #[test]
+    fn handlers_may_invoke_the_event_again() {
+        let event = Rc::new(LocalEvent::new());
+        let weak = Rc::downgrade(&event);
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        event.add_fn({
+            let calls = calls.clone();
+            move |depth: u32| {
+                calls.borrow_mut().push(depth);
+                if depth < 2 {
+                    weak.upgrade().unwrap().invoke(depth + 1);
+                }
+            }
+        });
+
+        assert_eq!(event.invoke(0), 1);
+        assert_eq!(*calls.borrow(), vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::Registration;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+/// A pending handler call, see [`Event::pump`](crate::Event::pump).
+struct Task<TEventArgs> {
+    /// The priority of the handler; lower values are called first.
+    priority: i32,
+    /// Orders tasks of equal priority by the time they were queued.
+    sequence: u64,
+    entry: Arc<Registration<TEventArgs>>,
+    args: TEventArgs,
+}
+
+impl<TEventArgs> Task<TEventArgs> {
+    /// The ordering key; the greatest key is taken from the heap first.
+    fn key(&self) -> (i32, u64) {
+        (self.priority, self.sequence)
+    }
+}
+
+impl<TEventArgs> PartialEq for Task<TEventArgs> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<TEventArgs> Eq for Task<TEventArgs> {}
+
+impl<TEventArgs> PartialOrd for Task<TEventArgs> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<TEventArgs> Ord for Task<TEventArgs> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed, since the heap yields its maximum first.
+        other.key().cmp(&self.key())
+    }
+}
+
+/// Handler calls queued by an invocation and executed when pumped.
+pub(crate) struct TaskQueue<TEventArgs> {
+    tasks: BinaryHeap<Task<TEventArgs>>,
+    /// The sequence number of the next task.
+    sequence: u64,
+}
+
+impl<TEventArgs> TaskQueue<TEventArgs> {
+    pub(crate) fn new() -> Self {
+        Self {
+            tasks: BinaryHeap::new(),
+            sequence: 0,
+        }
+    }
+
+    /// Queues the call of a handler.
+    pub(crate) fn push(&mut self, entry: Arc<Registration<TEventArgs>>, args: TEventArgs) {
+        let sequence = self.sequence;
+        self.sequence += 1;
+        self.tasks.push(Task {
+            priority: entry.priority,
+            sequence,
+            entry,
+            args,
+        });
+    }
+
+    /// Takes the call with the lowest priority, then the oldest.
+    pub(crate) fn pop(&mut self) -> Option<(Arc<Registration<TEventArgs>>, TEventArgs)> {
+        self.tasks.pop().map(|task| (task.entry, task.args))
+    }
+
+    /// Returns the number of queued calls.
+    pub(crate) fn len(&self) -> usize {
+        self.tasks.len()
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{accumulator, AddHandlerError, Event, EventHandle, RegistrationId};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+/// The results gathered by an invocation of a [`ReturningEvent`].
+type Results<TResult> = Vec<(RegistrationId, TResult)>;
+
+/// An event whose handlers return a value to the invoker.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::ReturningEvent;
+///
+/// let event = ReturningEvent::new();
+/// let handle = event.add_returning_fn(|value: i32| value * 2).unwrap();
+///
+/// let results = event.invoke_collect_by_id(21);
+/// assert_eq!(results[&handle.id()], 42);
+/// ```
+pub struct ReturningEvent<TEventArgs, TResult> {
+    event: Event<TEventArgs>,
+    result: PhantomData<fn() -> TResult>,
+}
+
+impl<TEventArgs, TResult> ReturningEvent<TEventArgs, TResult>
+where
+    TEventArgs: Clone,
+    TResult: 'static,
+{
+    pub fn new() -> Self {
+        Self {
+            event: Event::new(),
+            result: PhantomData,
+        }
+    }
+
+    /// Registers a closure whose result is returned to the invoker.
+    pub fn add_returning_fn<T>(
+        &self,
+        handler: T,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) -> TResult + Send + 'static,
+    {
+        // The identifier is only known once the handler is registered.
+        let id = Arc::new(Mutex::new(None));
+        let event = self.event.handlers.address();
+        let handle = self.event.add_fn({
+            let id = id.clone();
+            move |args| {
+                let result = handler(args);
+                let Some(id) = *id.lock().unwrap() else {
+                    return;
+                };
+                accumulator::with_current(event, |results: &Mutex<Results<TResult>>| {
+                    results.lock().unwrap().push((id, result))
+                });
+            }
+        })?;
+        *id.lock().unwrap() = Some(handle.id());
+        Ok(handle)
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Invokes the event, gathering the results of all handlers in invocation order.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    pub fn invoke_collect(&self, args: TEventArgs) -> Vec<TResult> {
+        self.invoke_gather(args)
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect()
+    }
+
+    /// Invokes the event, gathering the results of all handlers
+    /// keyed by the registration that produced them.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    pub fn invoke_collect_by_id(&self, args: TEventArgs) -> BTreeMap<RegistrationId, TResult> {
+        self.invoke_gather(args).into_iter().collect()
+    }
+
+    /// Invokes the event, gathering the results in invocation order.
+    fn invoke_gather(&self, args: TEventArgs) -> Results<TResult> {
+        let results = Mutex::new(Vec::new());
+        {
+            let _scope =
+                accumulator::AccumulatorScope::enter(self.event.handlers.address(), &results);
+            self.event.invoke(args);
+        }
+        results.into_inner().unwrap()
+    }
+}
+
+impl<TEventArgs, TResult> Default for ReturningEvent<TEventArgs, TResult>
+where
+    TEventArgs: Clone,
+    TResult: 'static,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_are_collected_in_invocation_order() {
+        let event = ReturningEvent::new();
+        let _handles: Vec<_> = [3, 1, 2]
+            .into_iter()
+            .map(|result| event.add_returning_fn(move |_: ()| result))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(event.invoke_collect(()), vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn results_are_keyed_by_handler() {
+        let event = ReturningEvent::new();
+        let handles: Vec<_> = (1..=3)
+            .map(|factor| event.add_returning_fn(move |value: i32| value * factor))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let results = event.invoke_collect_by_id(10);
+        assert_eq!(results.len(), 3);
+        for (handle, expected) in handles.iter().zip([10, 20, 30]) {
+            assert_eq!(results[&handle.id()], expected);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::cell::RefCell;
+
+thread_local! {
+    /// Whether the currently running routed invocations on this thread were handled,
+    /// innermost invocation last.
+    static HANDLED: RefCell<Vec<bool>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Marks the innermost routed invocation on this thread as handled.
+///
+/// Returns `false` if no routed invocation is running.
+pub(crate) fn mark_handled() -> bool {
+    HANDLED.with(|handled| match handled.borrow_mut().last_mut() {
+        Some(handled) => {
+            *handled = true;
+            true
+        }
+        None => false,
+    })
+}
+
+/// Tracks whether a routed invocation was handled until dropped.
+pub(crate) struct RouteScope {
+    _private: (),
+}
+
+impl RouteScope {
+    pub(crate) fn enter() -> Self {
+        HANDLED.with(|handled| handled.borrow_mut().push(false));
+        Self { _private: () }
+    }
+
+    /// Determines whether a handler marked the invocation as handled.
+    pub(crate) fn is_handled(&self) -> bool {
+        HANDLED.with(|handled| handled.borrow().last().copied().unwrap_or(false))
+    }
+}
+
+impl Drop for RouteScope {
+    fn drop(&mut self) {
+        HANDLED.with(|handled| handled.borrow_mut().pop());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_scopes_are_tracked_separately() {
+        assert!(!mark_handled());
+
+        let outer = RouteScope::enter();
+        {
+            let inner = RouteScope::enter();
+            assert!(mark_handled());
+            assert!(inner.is_handled());
+        }
+        assert!(!outer.is_handled());
+    }
+}
@@ -0,0 +1,9 @@
+/// Executes handler calls on behalf of an event, see
+/// [`Event::with_scheduler`](crate::Event::with_scheduler).
+///
+/// Implementations decide when and where a task runs, e.g. inline, on a
+/// dedicated worker or on a thread pool.
+pub trait Scheduler: Send + Sync {
+    /// Schedules the call of a single handler.
+    fn schedule(&self, task: Box<dyn FnOnce() + Send>);
+}
@@ -0,0 +1,189 @@
+use crate::{depth, AddHandlerError, Event, EventHandle};
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::marker::PhantomData;
+
+thread_local! {
+    /// The senders of the currently running invocations on this thread, innermost invocation
+    /// last, along with the address of the invoked event and its invocation depth.
+    static SENDERS: RefCell<Vec<(usize, usize, TypeId, *const ())>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// An event whose handlers receive a reference to the sender alongside the arguments,
+/// like .NET's `EventHandler` delegates.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::SenderEvent;
+/// use std::sync::{Arc, Mutex};
+///
+/// struct Button {
+///     label: &'static str,
+/// }
+///
+/// let clicked = SenderEvent::new();
+/// let seen = Arc::new(Mutex::new(None));
+/// let _handle = clicked.add_handler({
+///     let seen = seen.clone();
+///     move |sender: &Button, count: u32| *seen.lock().unwrap() = Some((sender.label, count))
+/// }).unwrap();
+///
+/// clicked.invoke_with_sender(&Button { label: "OK" }, 1);
+/// assert_eq!(*seen.lock().unwrap(), Some(("OK", 1)));
+/// ```
+pub struct SenderEvent<TSender, TEventArgs> {
+    event: Event<TEventArgs>,
+    sender: PhantomData<fn(&TSender)>,
+}
+
+impl<TSender, TEventArgs> SenderEvent<TSender, TEventArgs>
+where
+    TSender: 'static,
+    TEventArgs: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            event: Event::new(),
+            sender: PhantomData,
+        }
+    }
+
+    /// Registers a closure that receives the sender and the arguments of each invocation.
+    pub fn add_handler<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(&TSender, TEventArgs) + Send + 'static,
+    {
+        let event = self.event.handlers.address();
+        self.event.add_fn(move |args| {
+            with_current_sender(event, |sender: &TSender| handler(sender, args));
+        })
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Invokes the event, passing the sender to each handler.
+    ///
+    /// ## Arguments
+    /// * `sender` - The object raising the event.
+    /// * `args` - The event arguments.
+    ///
+    /// ## Returns
+    /// The number of handlers called.
+    pub fn invoke_with_sender(&self, sender: &TSender, args: TEventArgs) -> usize {
+        let _scope = SenderScope::enter(self.event.handlers.address(), sender);
+        self.event.invoke(args)
+    }
+}
+
+impl<TSender, TEventArgs> Default for SenderEvent<TSender, TEventArgs>
+where
+    TSender: 'static,
+    TEventArgs: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Calls `f` with the sender of the running invocation of the specified event,
+/// if it is of type `TSender`.
+fn with_current_sender<TSender, R>(event: usize, f: impl FnOnce(&TSender) -> R) -> Option<R>
+where
+    TSender: 'static,
+{
+    // The handlers run one level deeper than the invocation that provided the sender.
+    let depth = depth::current_depth(event).checked_sub(1)?;
+    let current = SENDERS.with(|senders| {
+        senders
+            .borrow()
+            .iter()
+            .rev()
+            .find(|(address, at, type_id, _)| {
+                (*address, *at, *type_id) == (event, depth, TypeId::of::<TSender>())
+            })
+            .map(|(.., ptr)| *ptr)
+    });
+    // The pointer was registered by a `SenderScope` that outlives this call.
+    current.map(|ptr| f(unsafe { &*(ptr as *const TSender) }))
+}
+
+/// Provides the sender to the handlers until dropped.
+struct SenderScope<'a, TSender> {
+    _sender: &'a TSender,
+}
+
+impl<'a, TSender> SenderScope<'a, TSender>
+where
+    TSender: 'static,
+{
+    fn enter(event: usize, sender: &'a TSender) -> Self {
+        let ptr = sender as *const TSender as *const ();
+        let entry = (
+            event,
+            depth::current_depth(event),
+            TypeId::of::<TSender>(),
+            ptr,
+        );
+        SENDERS.with(|senders| senders.borrow_mut().push(entry));
+        Self { _sender: sender }
+    }
+}
+
+impl<TSender> Drop for SenderScope<'_, TSender> {
+    fn drop(&mut self) {
+        SENDERS.with(|senders| senders.borrow_mut().pop());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn handlers_receive_sender_and_args() {
+        let event = SenderEvent::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let seen = seen.clone();
+            event.add_handler(move |sender: &String, value: i32| {
+                seen.lock().unwrap().push((sender.clone(), value))
+            })
+        }
+        .unwrap();
+
+        let (first, second) = (String::from("first"), String::from("second"));
+        assert_eq!(event.invoke_with_sender(&first, 1), 1);
+        event.invoke_with_sender(&second, 2);
+        assert_eq!(*seen.lock().unwrap(), vec![(first, 1), (second, 2)]);
+    }
+
+    #[test]
+    fn nested_invocations_observe_their_own_sender() {
+        let event = Arc::new(SenderEvent::new());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let (weak, seen) = (Arc::downgrade(&event), seen.clone());
+            event.add_handler(move |sender: &&str, nested: bool| {
+                if nested {
+                    weak.upgrade().unwrap().invoke_with_sender(&"inner", false);
+                }
+                seen.lock().unwrap().push(*sender);
+            })
+        }
+        .unwrap();
+
+        event.invoke_with_sender(&"outer", true);
+        assert_eq!(*seen.lock().unwrap(), vec!["inner", "outer"]);
+    }
+}
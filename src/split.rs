@@ -0,0 +1,168 @@
+use crate::{AddHandlerError, Event, EventHandle, EventInvocationError, FnEventHandlerDelegate};
+
+/// The half of a split [`Event`] that can only invoke the handlers, see [`Event::split`].
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::Event;
+/// use std::sync::{Arc, Mutex};
+///
+/// let (sender, source) = Event::new().split();
+/// let seen = Arc::new(Mutex::new(Vec::new()));
+/// let _handle = source.add_fn({
+///     let seen = seen.clone();
+///     move |value: i32| seen.lock().unwrap().push(value)
+/// }).unwrap();
+///
+/// sender.invoke(1);
+/// assert_eq!(*seen.lock().unwrap(), vec![1]);
+/// ```
+pub struct EventSender<TEventArgs> {
+    event: Event<TEventArgs>,
+}
+
+/// The half of a split [`Event`] that can only register handlers, see [`Event::split`].
+pub struct EventSource<TEventArgs> {
+    event: Event<TEventArgs>,
+}
+
+impl<TEventArgs> Event<TEventArgs> {
+    /// Splits the event into a half that can only invoke the handlers
+    /// and a half that can only register them.
+    ///
+    /// Both halves share the handlers; they are dropped once both halves are dropped.
+    pub fn split(self) -> (EventSender<TEventArgs>, EventSource<TEventArgs>) {
+        let source = EventSource {
+            event: self.clone(),
+        };
+        (EventSender { event: self }, source)
+    }
+}
+
+impl<TEventArgs> EventSender<TEventArgs> {
+    /// Invokes the event, see [`Event::invoke`].
+    ///
+    /// ## Returns
+    /// The number of handlers called.
+    pub fn invoke(&self, args: TEventArgs) -> usize
+    where
+        TEventArgs: Clone,
+    {
+        self.event.invoke(args)
+    }
+
+    /// Invokes the event, reporting invocations that did not reach any handler,
+    /// see [`Event::try_invoke`].
+    pub fn try_invoke(&self, args: TEventArgs) -> Result<(), EventInvocationError>
+    where
+        TEventArgs: Clone,
+    {
+        self.event.try_invoke(args)
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.event.is_empty()
+    }
+}
+
+impl<TEventArgs> EventSource<TEventArgs> {
+    /// Registers a closure, see [`Event::add_fn`].
+    pub fn add_fn<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        self.event.add_fn(handler)
+    }
+
+    /// Registers a closure that is called at most once, see [`Event::add_fnonce`].
+    #[cfg(not(feature = "no_fnonce"))]
+    pub fn add_fnonce<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: FnOnce(TEventArgs) + Send + 'static,
+    {
+        self.event.add_fnonce(handler)
+    }
+
+    /// Registers a mutable closure, see [`Event::add_fnmut`].
+    pub fn add_fnmut<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: FnMut(TEventArgs) + Send + 'static,
+    {
+        self.event.add_fnmut(handler)
+    }
+
+    /// Registers a closure taking the arguments by reference, see [`Event::add_fn_ref`].
+    pub fn add_fn_ref<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(&TEventArgs) + Send + 'static,
+    {
+        self.event.add_fn_ref(handler)
+    }
+
+    /// Registers a closure with the specified priority, see [`Event::add_fn_with_priority`].
+    pub fn add_fn_with_priority<T>(
+        &self,
+        handler: T,
+        priority: i32,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        self.event.add_fn_with_priority(handler, priority)
+    }
+
+    /// Registers a function, see [`Event::add_ptr`].
+    pub fn add_ptr(
+        &self,
+        handler: FnEventHandlerDelegate<TEventArgs>,
+    ) -> Result<EventHandle<TEventArgs>, AddHandlerError> {
+        self.event.add_ptr(handler)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn halves_share_the_handlers() {
+        let (sender, source) = Event::new().split();
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let recorder = |name: &'static str| {
+            let calls = calls.clone();
+            move |value: i32| calls.lock().unwrap().push((name, value))
+        };
+
+        assert_eq!(sender.try_invoke(0), Err(EventInvocationError::NoHandlers));
+        let first = source.add_fn(recorder("first")).unwrap();
+        let _second = source.add_fn_with_priority(recorder("second"), -1).unwrap();
+        assert_eq!(sender.len(), 2);
+
+        assert_eq!(sender.invoke(1), 2);
+        assert_eq!(*calls.lock().unwrap(), vec![("second", 1), ("first", 1)]);
+
+        drop(first);
+        assert_eq!(sender.len(), 1);
+    }
+
+    #[test]
+    fn handlers_live_as_long_as_either_half() {
+        let (sender, source) = Event::<()>::new().split();
+        let handle = source.add_fnmut(|_| {}).unwrap();
+
+        drop(source);
+        assert!(handle.is_valid());
+        assert_eq!(sender.invoke(()), 1);
+
+        drop(sender);
+        assert!(!handle.is_valid());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{AddHandlerError, Event, EventHandle};
+use std::sync::Mutex;
+
+/// An event that holds a current value and notifies handlers of each change
+/// with both the old and the new value.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::StateEvent;
+/// use std::sync::{Arc, Mutex};
+///
+/// let state = StateEvent::new(0);
+/// let seen = Arc::new(Mutex::new(None));
+/// let _handle = state.add_fn_change({
+///     let seen = seen.clone();
+///     move |old: &i32, new: &i32| *seen.lock().unwrap() = Some((*old, *new))
+/// }).unwrap();
+///
+/// state.set(3);
+/// assert_eq!(*seen.lock().unwrap(), Some((0, 3)));
+/// assert_eq!(state.get(), 3);
+/// ```
+pub struct StateEvent<T>
+where
+    T: Clone,
+{
+    event: Event<(T, T)>,
+    value: Mutex<T>,
+}
+
+impl<T> StateEvent<T>
+where
+    T: Clone,
+{
+    /// Creates the event with the specified initial value.
+    pub fn new(initial: T) -> Self {
+        Self {
+            event: Event::new(),
+            value: Mutex::new(initial),
+        }
+    }
+
+    /// Registers a closure that receives the old and the new value of each change.
+    pub fn add_fn_change<H>(&self, handler: H) -> Result<EventHandle<(T, T)>, AddHandlerError>
+    where
+        H: Fn(&T, &T) + Send + 'static,
+    {
+        self.event
+            .add_fn(move |(old, new): (T, T)| handler(&old, &new))
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the current value.
+    pub fn get(&self) -> T {
+        self.value.lock().unwrap().clone()
+    }
+
+    /// Replaces the current value and invokes the handlers with the old and the new value.
+    ///
+    /// The value is stored before the handlers are called, so handlers
+    /// observe the new value through [`StateEvent::get`].
+    ///
+    /// ## Arguments
+    /// * `new` - The new value.
+    pub fn set(&self, new: T) {
+        let old = std::mem::replace(&mut *self.value.lock().unwrap(), new.clone());
+        self.event.invoke((old, new));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn handlers_observe_old_and_new_value() {
+        let state = StateEvent::new(0);
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let _handle = {
+            let changes = changes.clone();
+            state.add_fn_change(move |old: &i32, new: &i32| {
+                changes.lock().unwrap().push((*old, *new))
+            })
+        }
+        .unwrap();
+
+        state.set(1);
+        state.set(2);
+        assert_eq!(*changes.lock().unwrap(), vec![(0, 1), (1, 2)]);
+    }
+}
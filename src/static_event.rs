@@ -0,0 +1,111 @@
+/// An event whose handlers all share the same concrete type.
+///
+/// Handlers are stored inline in a [`Vec`] and called through static dispatch,
+/// trading the heterogeneity of [`Event`](crate::Event) for invocation speed.
+/// Since there are no handles, handlers live for as long as the event does.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::StaticEvent;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// fn counter(total: Arc<AtomicUsize>) -> impl Fn(usize) {
+///     move |amount| {
+///         total.fetch_add(amount, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let total = Arc::new(AtomicUsize::new(0));
+/// let mut event = StaticEvent::new();
+/// event.add(counter(total.clone()));
+/// event.add(counter(total.clone()));
+///
+/// event.invoke(21);
+/// assert_eq!(total.load(Ordering::Relaxed), 42);
+/// ```
+pub struct StaticEvent<TEventArgs, H>
+where
+    H: Fn(TEventArgs),
+{
+    handlers: Vec<H>,
+    _args: std::marker::PhantomData<fn(TEventArgs)>,
+}
+
+impl<TEventArgs, H> StaticEvent<TEventArgs, H>
+where
+    H: Fn(TEventArgs),
+{
+    pub const fn new() -> Self {
+        Self {
+            handlers: Vec::new(),
+            _args: std::marker::PhantomData,
+        }
+    }
+
+    /// Registers a handler.
+    pub fn add(&mut self, handler: H) {
+        self.handlers.push(handler);
+    }
+
+    /// Removes all handlers.
+    pub fn clear(&mut self) {
+        self.handlers.clear();
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Invokes the event.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    pub fn invoke(&self, args: TEventArgs)
+    where
+        TEventArgs: Clone,
+    {
+        for handler in &self.handlers {
+            handler(args.clone());
+        }
+    }
+}
+
+impl<TEventArgs, H> Default for StaticEvent<TEventArgs, H>
+where
+    H: Fn(TEventArgs),
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    fn recorder(name: &'static str, calls: Arc<Mutex<Vec<(&'static str, i32)>>>) -> impl Fn(i32) {
+        move |value| calls.lock().unwrap().push((name, value))
+    }
+
+    #[test]
+    fn invokes_all_handlers_in_order() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let mut event = StaticEvent::new();
+        event.add(recorder("a", calls.clone()));
+        event.add(recorder("b", calls.clone()));
+        event.add(recorder("c", calls.clone()));
+        assert_eq!(event.len(), 3);
+
+        event.invoke(7);
+        assert_eq!(*calls.lock().unwrap(), vec![("a", 7), ("b", 7), ("c", 7)]);
+    }
+}
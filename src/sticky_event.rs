@@ -0,0 +1,145 @@
+use crate::{AddHandlerError, Event, EventHandle};
+use std::sync::Mutex;
+
+/// An event that remembers the last invoked value and delivers it to handlers
+/// registered afterwards, similar to sticky broadcasts.
+///
+/// ## Example
+///
+/// ```
+/// use event_handler::StickyEvent;
+/// use std::sync::{Arc, Mutex};
+///
+/// let event = StickyEvent::new();
+/// event.invoke(7);
+///
+/// let value = Arc::new(Mutex::new(0));
+/// let _handle = event.add_fn({
+///     let value = value.clone();
+///     move |amount| *value.lock().unwrap() = amount
+/// }).unwrap();
+///
+/// assert_eq!(*value.lock().unwrap(), 7);
+/// ```
+pub struct StickyEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    event: Event<TEventArgs>,
+    sticky: Mutex<Option<TEventArgs>>,
+}
+
+impl<TEventArgs> StickyEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    pub fn new() -> Self {
+        Self {
+            event: Event::new(),
+            sticky: Mutex::new(None),
+        }
+    }
+
+    /// Registers a closure, then calls it with the sticky value, if any.
+    ///
+    /// The closure is only called if it was registered. As the sticky value is read after
+    /// registering, no invocation is missed; an invocation racing with the registration
+    /// may deliver its value twice, though.
+    pub fn add_fn<T>(&self, handler: T) -> Result<EventHandle<TEventArgs>, AddHandlerError>
+    where
+        T: Fn(TEventArgs) + Send + 'static,
+    {
+        let handle = self.event.add_fn(handler)?;
+
+        if let Some(args) = self.sticky() {
+            let handlers = &self.event.handlers;
+            if let Some((_, entry)) = handlers.current().get_key_value(&handle.key) {
+                handlers.call(entry, args);
+            }
+        }
+        Ok(handle)
+    }
+
+    /// Returns the number of currently registered handlers.
+    pub fn len(&self) -> usize {
+        self.event.len()
+    }
+
+    /// Determines whether no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the sticky value, i.e. the arguments of the last invocation.
+    pub fn sticky(&self) -> Option<TEventArgs> {
+        self.sticky.lock().unwrap().clone()
+    }
+
+    /// Forgets the sticky value; handlers registered afterwards receive nothing
+    /// until the event is invoked again.
+    pub fn clear_sticky(&self) {
+        *self.sticky.lock().unwrap() = None;
+    }
+
+    /// Invokes the event and remembers the arguments as the sticky value.
+    ///
+    /// ## Arguments
+    /// * `args` - The event arguments.
+    pub fn invoke(&self, args: TEventArgs) {
+        *self.sticky.lock().unwrap() = Some(args.clone());
+        self.event.invoke(args);
+    }
+}
+
+impl<TEventArgs> Default for StickyEvent<TEventArgs>
+where
+    TEventArgs: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn recorder(calls: &Arc<Mutex<Vec<i32>>>) -> impl Fn(i32) + Send + 'static {
+        let calls = calls.clone();
+        move |value| calls.lock().unwrap().push(value)
+    }
+
+    #[test]
+    fn rejected_handlers_do_not_receive_sticky_value() {
+        let event = StickyEvent::new();
+        event.invoke(7);
+        event.event.freeze();
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        assert_eq!(
+            event.add_fn(recorder(&calls)).err(),
+            Some(AddHandlerError::Frozen)
+        );
+        assert!(calls.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn late_handlers_receive_sticky_value() {
+        let event = StickyEvent::new();
+        event.invoke(7);
+
+        let late = Arc::new(Mutex::new(Vec::new()));
+        let _late = event.add_fn(recorder(&late)).unwrap();
+        assert_eq!(*late.lock().unwrap(), vec![7]);
+
+        event.clear_sticky();
+        let cleared = Arc::new(Mutex::new(Vec::new()));
+        let _cleared = event.add_fn(recorder(&cleared)).unwrap();
+        assert!(cleared.lock().unwrap().is_empty());
+
+        event.invoke(8);
+        assert_eq!(*late.lock().unwrap(), vec![7, 8]);
+        assert_eq!(*cleared.lock().unwrap(), vec![8]);
+    }
+}
@@ -0,0 +1,37 @@
+use crate::EventHandle;
+use futures::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A stream that keeps the handler feeding it registered for as long as the stream
+/// exists, see [`Event::subscribe_stream`](crate::Event::subscribe_stream).
+pub(crate) struct Subscription<S, TEventArgs> {
+    /// The stream fed by the handler.
+    stream: S,
+    /// Unregisters the handler when the stream is dropped.
+    _handle: EventHandle<TEventArgs>,
+}
+
+impl<S, TEventArgs> Subscription<S, TEventArgs> {
+    pub(crate) fn new(stream: S, handle: EventHandle<TEventArgs>) -> Self {
+        Self {
+            stream,
+            _handle: handle,
+        }
+    }
+}
+
+impl<S, TEventArgs> Stream for Subscription<S, TEventArgs>
+where
+    S: Stream + Unpin,
+{
+    type Item = S::Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
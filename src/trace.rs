@@ -0,0 +1,33 @@
+use std::cell::Cell;
+
+thread_local! {
+    /// The trace identifier of the invocation currently running on this thread.
+    static CURRENT_TRACE_ID: Cell<u128> = const { Cell::new(UNTRACED) };
+}
+
+/// The trace identifier observed by handlers of untraced invocations.
+pub const UNTRACED: u128 = 0;
+
+/// Returns the trace identifier of the invocation currently running on this thread.
+pub(crate) fn current_trace_id() -> u128 {
+    CURRENT_TRACE_ID.with(Cell::get)
+}
+
+/// Sets the trace identifier for the current thread until the scope is dropped.
+pub(crate) struct TraceScope {
+    /// The identifier to restore when leaving the scope.
+    previous: u128,
+}
+
+impl TraceScope {
+    pub(crate) fn enter(trace_id: u128) -> Self {
+        let previous = CURRENT_TRACE_ID.with(|current| current.replace(trace_id));
+        Self { previous }
+    }
+}
+
+impl Drop for TraceScope {
+    fn drop(&mut self) {
+        CURRENT_TRACE_ID.with(|current| current.set(self.previous));
+    }
+}